@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext, EguiSettings};
+use serde::{Deserialize, Serialize};
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Accessibility>()
+            .add_system(apply_accessibility);
+    }
+}
+
+// applies the options whenever they change, they are stored with the rest of the settings
+fn apply_accessibility(
+    mut egui_context: ResMut<EguiContext>,
+    mut egui_settings: ResMut<EguiSettings>,
+    accessibility: Res<Accessibility>,
+) {
+    if !accessibility.is_changed() {
+        return;
+    }
+    egui_settings.scale_factor = accessibility.ui_scale as f64;
+    egui_context.ctx_mut().set_visuals(accessibility.visuals());
+}
+
+// colors that stay apart with any kind of color blindness (okabe and ito), for the names in
+// the dialogue. the first one is the player's
+pub const SAFE_NAME_COLORS: [(u8, u8, u8); 6] = [
+    (86, 180, 233),
+    (230, 159, 0),
+    (0, 158, 115),
+    (213, 94, 0),
+    (204, 121, 167),
+    (240, 228, 66),
+];
+
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Accessibility {
+    // how large the interface is drawn
+    pub ui_scale: f32,
+    pub dark_theme: bool,
+    // black and white with thicker outlines
+    pub high_contrast: bool,
+    // the font listed as `dyslexia_friendly` in the font file comes first
+    pub dyslexia_font: bool,
+    // no typing out or shaking of dialogue
+    pub reduced_motion: bool,
+    // dialogue names use `SAFE_NAME_COLORS` instead of the colors in the npc file
+    pub safe_name_colors: bool,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Accessibility {
+            ui_scale: 1.0,
+            dark_theme: true,
+            high_contrast: false,
+            dyslexia_font: false,
+            reduced_motion: false,
+            safe_name_colors: false,
+        }
+    }
+}
+
+impl Accessibility {
+    pub const UI_SCALES: std::ops::RangeInclusive<f32> = 0.75..=2.0;
+    pub fn visuals(&self) -> egui::Visuals {
+        let mut visuals = if self.dark_theme {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+        if !self.high_contrast {
+            return visuals;
+        }
+        let (fg, bg) = if self.dark_theme {
+            (egui::Color32::WHITE, egui::Color32::BLACK)
+        } else {
+            (egui::Color32::BLACK, egui::Color32::WHITE)
+        };
+        visuals.override_text_color = Some(fg);
+        visuals.window_fill = bg;
+        visuals.panel_fill = bg;
+        visuals.extreme_bg_color = bg;
+        visuals.window_stroke = egui::Stroke::new(2.0, fg);
+        visuals.selection.stroke = egui::Stroke::new(2.0, fg);
+        let widgets = &mut visuals.widgets;
+        for widget in [
+            &mut widgets.noninteractive,
+            &mut widgets.inactive,
+            &mut widgets.hovered,
+            &mut widgets.active,
+            &mut widgets.open,
+        ] {
+            widget.fg_stroke = egui::Stroke::new(widget.fg_stroke.width.max(1.5), fg);
+            widget.bg_stroke = egui::Stroke::new(widget.bg_stroke.width.max(1.0), fg);
+        }
+        visuals
+    }
+    // the options in the accessibility tab of the settings
+    pub fn show(&mut self, ui: &mut egui::Ui, tr: impl Fn(&str) -> String) {
+        // the size is changed once the slider is let go, or the slider would move under the mouse
+        let id = ui.make_persistent_id("ui_scale");
+        let mut ui_scale = ui.data().get_temp(id).unwrap_or(self.ui_scale);
+        let slider = egui::Slider::new(&mut ui_scale, Self::UI_SCALES).step_by(0.05);
+        if ui.add(slider.text(tr("Interface size"))).dragged() {
+            ui.data().insert_temp(id, ui_scale);
+        } else {
+            ui.data().remove::<f32>(id);
+            self.ui_scale = ui_scale;
+        }
+        ui.checkbox(&mut self.dark_theme, tr("Dark theme"));
+        ui.checkbox(&mut self.high_contrast, tr("High contrast"));
+        ui.checkbox(&mut self.dyslexia_font, tr("Dyslexia-friendly font"));
+        ui.checkbox(&mut self.reduced_motion, tr("Reduce motion"));
+        ui.checkbox(&mut self.safe_name_colors, tr("Colorblind-safe name colors"));
+    }
+}
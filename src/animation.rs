@@ -0,0 +1,228 @@
+use crate::{loading, settings};
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<AnimationFile>::new(&["anim.ron"]))
+            .init_resource::<AnimationFileHandle>()
+            .init_resource::<Animations>()
+            .add_startup_system(load_files)
+            .add_system(prepare_animations)
+            .add_system(animate);
+    }
+}
+
+fn load_files(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut animation_handle: ResMut<AnimationFileHandle>,
+) {
+    animation_handle.0 = loading.load(&asset_server, "animations.anim.ron");
+}
+
+fn prepare_animations(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    mut animations: ResMut<Animations>,
+    animation_handle: Res<AnimationFileHandle>,
+    animation_file: Res<Assets<AnimationFile>>,
+) {
+    if animations.loaded {
+        return;
+    }
+    let Some(animation_file) = animation_file.get(&animation_handle.0) else { return; };
+
+    for (id, character) in animation_file.characters.iter() {
+        let mut character = character.clone();
+        if let Some(sheet) = &character.sheet {
+            character.atlas = sheet.load(&asset_server, &mut loading, &mut atlases);
+        }
+        animations.characters.insert(id.to_owned(), character);
+    }
+    animations.loaded = true;
+}
+
+// advances every animator and shows its current frame
+fn animate(
+    time: Res<Time>,
+    animations: Res<Animations>,
+    settings: Res<settings::Settings>,
+    mut query: Query<(&mut Animator, &mut TextureAtlasSprite)>,
+) {
+    for (mut animator, mut sprite) in query.iter_mut() {
+        let Some(clip) = animations.clip(&animator.character, &animator.clip) else { continue; };
+        if clip.frames.is_empty() {
+            continue;
+        }
+
+        animator.timer += time.delta_seconds() * settings.animation_speed;
+        let interval = 1.0 / clip.fps.max(0.01);
+        while animator.timer >= interval {
+            animator.timer -= interval;
+            animator.frame += 1;
+            if animator.frame < clip.frames.len() {
+                continue;
+            }
+            if let Some(next) = animator.next.take() {
+                // the clip played once is done, go back to what was playing before
+                animator.clip = next;
+                animator.frame = 0;
+                break;
+            } else if clip.once {
+                animator.frame = clip.frames.len() - 1;
+            } else {
+                animator.frame = 0;
+            }
+        }
+
+        let Some(clip) = animations.clip(&animator.character, &animator.clip) else { continue; };
+        let index = clip.frames[animator.frame % clip.frames.len()];
+        if sprite.index != index {
+            sprite.index = index;
+        }
+        let color = match clip.tint {
+            Some((r, g, b, a)) => Color::rgba(r, g, b, a),
+            None => Color::WHITE,
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+
+// a named sequence of frames, such as "idle" or "walk_left"
+#[derive(Clone, Deserialize)]
+pub struct Clip {
+    // indices into the character's sprite sheet
+    frames: Vec<usize>,
+    // frames per second
+    fps: f32,
+    // stops at the last frame instead of looping
+    #[serde(default)]
+    once: bool,
+    #[serde(default)]
+    tint: Option<(f32, f32, f32, f32)>,
+}
+
+// a grid of equally sized frames in a single image
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SpriteSheet {
+    image: String,
+    tile_size: (f32, f32),
+    columns: usize,
+    rows: usize,
+    // the gap between two frames in pixels
+    #[serde(default)]
+    padding: f32,
+}
+
+#[derive(Clone, Deserialize)]
+struct Character {
+    // npcs declare their sheets in the npc file, this is for the player
+    #[serde(default)]
+    sheet: Option<SpriteSheet>,
+    clips: HashMap<String, Clip>,
+    #[serde(skip)]
+    atlas: Handle<TextureAtlas>,
+}
+
+// the sprite sheets and clips of every character, by character id
+#[derive(Resource, Default)]
+pub struct Animations {
+    characters: HashMap<String, Character>,
+    pub loaded: bool,
+}
+
+#[derive(Deserialize, bevy::reflect::TypeUuid)]
+#[uuid = "a7c3e9b1-4f2d-4e8a-b6c5-19d0f3e8a2b7"]
+struct AnimationFile {
+    characters: HashMap<String, Character>,
+}
+
+#[derive(Resource, Default)]
+struct AnimationFileHandle(Handle<AnimationFile>);
+
+// plays the clips of a character on the sprite of the same entity
+#[derive(Component)]
+pub struct Animator {
+    character: String,
+    clip: String,
+    // the clip to go back to after a clip that plays once
+    next: Option<String>,
+    frame: usize,
+    timer: f32,
+}
+
+impl SpriteSheet {
+    pub fn load(
+        &self,
+        asset_server: &AssetServer,
+        loading: &mut loading::LoadingAssets,
+        atlases: &mut Assets<TextureAtlas>,
+    ) -> Handle<TextureAtlas> {
+        atlases.add(TextureAtlas::from_grid(
+            loading.load(asset_server, &self.image),
+            Vec2::new(self.tile_size.0, self.tile_size.1),
+            self.columns,
+            self.rows,
+            Some(Vec2::splat(self.padding)),
+            None,
+        ))
+    }
+}
+
+impl Animations {
+    // the sprite sheet of a character that isn't an npc, to be used in a `SpriteSheetBundle`
+    pub fn atlas(&self, character: &str) -> Handle<TextureAtlas> {
+        match self.characters.get(character) {
+            Some(character) => character.atlas.to_owned(),
+            None => Handle::default(),
+        }
+    }
+    // falls back from "walk_left" to "walk", and then to "idle"
+    fn clip(&self, character: &str, name: &str) -> Option<&Clip> {
+        let clips = &self.characters.get(character)?.clips;
+        clips
+            .get(name)
+            .or_else(|| clips.get(name.split('_').next()?))
+            .or_else(|| clips.get("idle"))
+    }
+}
+
+impl Animator {
+    pub fn new(character: &str) -> Animator {
+        Animator {
+            character: character.to_string(),
+            clip: "idle".to_string(),
+            next: None,
+            frame: 0,
+            timer: 0.0,
+        }
+    }
+    // switches to a looping clip, waits for a clip that plays once to finish first
+    pub fn play(&mut self, clip: &str) {
+        if self.next.is_some() {
+            self.next = Some(clip.to_string());
+        } else if self.clip != clip {
+            self.clip = clip.to_string();
+            self.frame = 0;
+            self.timer = 0.0;
+        }
+    }
+    // plays a clip once, then goes back to the current clip
+    pub fn play_once(&mut self, clip: &str) {
+        if self.next.is_none() {
+            self.next = Some(std::mem::replace(&mut self.clip, clip.to_string()));
+        } else {
+            self.clip = clip.to_string();
+        }
+        self.frame = 0;
+        self.timer = 0.0;
+    }
+}
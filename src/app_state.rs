@@ -0,0 +1,49 @@
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+
+pub struct AppStatePlugin;
+
+impl Plugin for AppStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state(AppState::Loading);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AppState {
+    // waiting for every asset to be loaded
+    Loading,
+    Title,
+    // showing a cg
+    Cutscene,
+    // walking around the map
+    Exploring,
+    // talking to a friendly npc
+    Dialogue,
+    // fighting a hostile npc
+    Battle,
+    // pushed on top of the other states when the player presses escape
+    Paused,
+    GameOver,
+    // pushed on top of exploring to paint the map, only with the `editor` feature
+    Editor,
+}
+
+impl AppState {
+    // the states in which the game world is running
+    pub fn is_playing(&self) -> bool {
+        matches!(
+            self,
+            AppState::Exploring | AppState::Dialogue | AppState::Battle
+        )
+    }
+}
+
+// run criteria for systems that run whenever the game world is running
+pub fn playing(app_state: Res<State<AppState>>) -> ShouldRun {
+    if app_state.current().is_playing() {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{commands, game_backend, npcs};
+
+// changes that cheat commands can't make to the game state alone,
+// they are carried out by the debug plugin
+pub enum Cheat {
+    Teleport((usize, usize)),
+    SpawnNpc(String),
+    SkipCgs(bool),
+}
+
+pub struct SetLevelCommand;
+
+impl commands::GameCommand for SetLevelCommand {
+    fn synopsis(&self) -> &'static str {
+        "setlevel <level>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"setlevel - Change your level (debug)
+
+SYNOPSIS
+    setlevel <level>
+
+DESCRIPTION
+    Set your level, which decides the commands you can run.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        let Some(level) = argv.get(1) else { return Err(format!("Usage: {}", self.synopsis())); };
+        let Ok(level) = level.parse::<i32>() else {
+            return Err(format!("`{}' is not a valid level", level));
+        };
+        game_state.player_level = level;
+        Ok(format!("Your level is now {}", level).into())
+    }
+}
+
+pub struct TeleportCommand;
+
+impl commands::GameCommand for TeleportCommand {
+    fn synopsis(&self) -> &'static str {
+        "teleport <x> <y>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"teleport - Move to another tile (debug)
+
+SYNOPSIS
+    teleport <x> <y>
+
+DESCRIPTION
+    Move to the tile at the given coordinates, which has to be a road.
+    The coordinates of the tiles are shown in the debug overlay.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        let (Some(x), Some(y)) = (argv.get(1), argv.get(2)) else {
+            return Err(format!("Usage: {}", self.synopsis()));
+        };
+        let (Ok(x), Ok(y)) = (x.parse::<usize>(), y.parse::<usize>()) else {
+            return Err(format!("`{} {}' are not valid coordinates", x, y));
+        };
+        game_state.cheats.push(Cheat::Teleport((x, y)));
+        Ok(format!("Teleporting to ({}, {})", x, y).into())
+    }
+}
+
+pub struct SetProgressCommand;
+
+impl commands::GameCommand for SetProgressCommand {
+    fn synopsis(&self) -> &'static str {
+        "setprogress <intro|tutorial|panel|terminal>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"setprogress - Jump to a chapter of the game (debug)
+
+SYNOPSIS
+    setprogress <intro|tutorial|panel|terminal>
+
+DESCRIPTION
+    Change how far you are in the game, which decides the parts of the
+    interface that are shown.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        use game_backend::GameProgress::*;
+        let Some(progress) = argv.get(1) else {
+            return Err(format!("Usage: {}", self.synopsis()));
+        };
+        game_state.game_progress = match progress.to_lowercase().as_str() {
+            "intro" => Intro,
+            "tutorial" => Tutorial,
+            "panel" => HasPanel,
+            "terminal" => HasTerminal,
+            _ => return Err(format!("Unknown progress: {}", progress)),
+        };
+        Ok(format!("Progress set to {}", progress).into())
+    }
+}
+
+pub struct SpawnCommand;
+
+impl commands::GameCommand for SpawnCommand {
+    fn synopsis(&self) -> &'static str {
+        "spawn <npc>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"spawn - Meet an npc (debug)
+
+SYNOPSIS
+    spawn <npc>
+
+DESCRIPTION
+    Bring the npc to where you are standing, even if you have already
+    met them. The encounter starts from the beginning.
+
+EXAMPLES
+    spawn bob
+        Fight Segfault right away.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        let Some(id) = argv.get(1) else { return Err(format!("Usage: {}", self.synopsis())); };
+        let id = id.to_lowercase();
+        if npcs::get_npc_by_id(&id).is_none() {
+            return Err(format!("Unknown npc: {}", id));
+        }
+        let output = format!("Spawning {}", id).into();
+        game_state.cheats.push(Cheat::SpawnNpc(id));
+        Ok(output)
+    }
+}
+
+pub struct HealCommand;
+
+impl commands::GameCommand for HealCommand {
+    fn synopsis(&self) -> &'static str {
+        "heal"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"heal - Restore your hitpoints (debug)
+
+SYNOPSIS
+    heal
+
+DESCRIPTION
+    Restore all of your hitpoints and remove every effect on you.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        _argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        game_state.player_hitpoints = game_state.effective_max_hp();
+        game_state.player_effects.clear();
+        Ok("You feel brand new".into())
+    }
+}
+
+pub struct SkipCgCommand;
+
+impl commands::GameCommand for SkipCgCommand {
+    fn synopsis(&self) -> &'static str {
+        "skipcg [on|off]"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"skipcg - Skip cutscenes (debug)
+
+SYNOPSIS
+    skipcg [on|off]
+
+DESCRIPTION
+    Skip every cutscene from now on, including the intro of a new game,
+    until `skipcg off' is run.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        let skip = match argv.get(1).copied() {
+            None | Some("on") => true,
+            Some("off") => false,
+            Some(arg) => return Err(format!("Unknown argument: {}", arg)),
+        };
+        game_state.cheats.push(Cheat::SkipCgs(skip));
+        Ok(if skip {
+            "Cutscenes will be skipped".into()
+        } else {
+            "Cutscenes will be shown".into()
+        })
+    }
+}
+
+pub struct GodCommand;
+
+impl commands::GameCommand for GodCommand {
+    fn synopsis(&self) -> &'static str {
+        "god"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"god - Toggle invincibility (debug)
+
+SYNOPSIS
+    god
+
+DESCRIPTION
+    Keep your hitpoints full no matter what hits you, until `god' is run
+    again.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        _argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        game_state.god_mode = !game_state.god_mode;
+        Ok(if game_state.god_mode {
+            "God mode on".into()
+        } else {
+            "God mode off".into()
+        })
+    }
+}
@@ -26,16 +26,30 @@ DESCRIPTION
         &self,
         game_state: &mut game_backend::GameState,
         argv: &[&str],
-    ) -> Result<String, String> {
-        let commands = commands::list_commands("", game_state.player_level);
+    ) -> Result<commands::CommandOutput, String> {
+        let commands = commands::list_commands("", game_state.effective_level());
         if argv.contains(&"-v") {
-            Ok(commands
+            let rows = commands
                 .iter()
-                .map(|name| commands::get_command_by_name(name).unwrap().synopsis())
-                .collect::<Vec<_>>()
-                .join("\n"))
+                .map(|name| {
+                    let synopsis = commands::get_command_by_name(name).unwrap().synopsis();
+                    vec![
+                        commands::Span::link(*name, format!("man {}", name)),
+                        commands::Span::styled(synopsis, commands::SpanStyle::Code),
+                    ]
+                })
+                .collect();
+            Ok(commands::CommandOutput::new().table(&["COMMAND", "SYNOPSIS"], rows))
         } else {
-            Ok(commands.join(" "))
+            // clicking on a command name shows its help
+            let mut spans = vec![];
+            for name in commands.iter() {
+                if !spans.is_empty() {
+                    spans.push(commands::Span::plain(" "));
+                }
+                spans.push(commands::Span::link(*name, format!("help {}", name)));
+            }
+            Ok(commands::CommandOutput::new().line(spans))
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::{commands, game_backend, npcs};
+use crate::{commands, game_backend, npcs, sound, status_effects};
 
 pub struct FireballCommand;
 
@@ -16,6 +16,8 @@ DESCRIPTION
     Throw a fireball at your enemy that deals the damage amount specified.
     The number must be an positive integer not larger than your ATK stat.
     If `damage' is omitted, deal damage equal to your ATK.
+    The enemy keeps burning for 3 turns, taking a quarter of the damage
+    each turn.
 
 EXAMPLES
     fireball 10
@@ -29,10 +31,10 @@ EXAMPLES
         &self,
         game_state: &mut game_backend::GameState,
         argv: &[&str],
-    ) -> Result<String, String> {
+    ) -> Result<commands::CommandOutput, String> {
         let damage = if let Some(damage) = argv.get(1) {
             match damage.parse::<i32>() {
-                Ok(damage) if 0 < damage => damage,
+                Ok(damage) if 0 < damage && damage <= game_state.effective_atk() => damage,
                 Ok(_) => {
                     return Err(format!(
                         "Damage should be positive and not larger than your ATK"
@@ -41,13 +43,24 @@ EXAMPLES
                 Err(_) => return Err(format!("`{}' is not a valid number", damage)),
             }
         } else {
-            game_state.player_atk
+            game_state.effective_atk()
         };
         if game_state.in_battle {
             game_state
                 .action_queue
                 .push(npcs::PlayerAction::Attack(damage));
-            Ok(format!("Dealt {} damage", damage))
+            game_state.sounds.push(sound::Sound::Fireball);
+            // the flames keep burning for a few turns
+            game_state.enemy_effects.apply(status_effects::StatusEffect::new(
+                status_effects::EffectKind::Burn,
+                status_effects::EffectDuration::Turns(3),
+                (damage / 4).max(1),
+            ));
+            Ok(commands::CommandOutput::new().line(vec![
+                commands::Span::plain("Dealt "),
+                commands::Span::styled(damage.to_string(), commands::SpanStyle::Success),
+                commands::Span::plain(" damage"),
+            ]))
         } else {
             Err("Not in battle".to_string())
         }
@@ -28,13 +28,20 @@ EXAMPLES
         &self,
         game_state: &mut game_backend::GameState,
         argv: &[&str],
-    ) -> Result<String, String> {
+    ) -> Result<commands::CommandOutput, String> {
         if let Some(command_name) = argv.get(1) {
             if let Some(command_box) = commands::get_command_by_name(command_name) {
-                if game_state.player_level < command_box.required_level() {
+                if game_state.effective_level() < command_box.required_level() {
                     Err("You don't have access to that command".to_string())
                 } else {
-                    Ok(command_box.synopsis().to_string())
+                    Ok(commands::CommandOutput::new().line(vec![
+                        commands::Span::styled(command_box.synopsis(), commands::SpanStyle::Code),
+                        commands::Span::styled("  see ", commands::SpanStyle::Muted),
+                        commands::Span::link(
+                            format!("man {}", command_name.to_lowercase()),
+                            format!("man {}", command_name.to_lowercase()),
+                        ),
+                    ]))
                 }
             } else {
                 Err(format!("No such command: {}", command_name))
@@ -0,0 +1,348 @@
+use crate::commands::GameCommand;
+use crate::{commands, game_backend, inventory, npcs};
+
+pub struct InventoryCommand;
+
+impl commands::GameCommand for InventoryCommand {
+    fn synopsis(&self) -> &'static str {
+        "inventory"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"inventory - List your items
+
+SYNOPSIS
+    inventory
+    inv
+
+DESCRIPTION
+    Show the items you are carrying.
+    Items can be found lying around in the inferno. Walk over them to pick
+    them up.
+
+SEE ALSO
+    use, drop, give
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        _argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        let inventory = &game_state.inventory;
+        if inventory.is_empty() {
+            return Ok(
+                commands::CommandOutput::new().line(vec![commands::Span::styled(
+                    "You don't have any items",
+                    commands::SpanStyle::Muted,
+                )]),
+            );
+        }
+        let rows = inventory
+            .iter()
+            .map(|(id, count)| {
+                let description = match inventory.get(id) {
+                    Some(item) => item.description.to_owned(),
+                    None => String::new(),
+                };
+                vec![
+                    commands::Span::link(id, format!("use {}", id)),
+                    commands::Span::plain(count.to_string()),
+                    commands::Span::plain(inventory.item_name(id)),
+                    commands::Span::styled(description, commands::SpanStyle::Muted),
+                ]
+            })
+            .collect();
+        Ok(commands::CommandOutput::new().table(&["ITEM", "COUNT", "NAME", "DESCRIPTION"], rows))
+    }
+}
+
+pub struct UseCommand;
+
+impl commands::GameCommand for UseCommand {
+    fn synopsis(&self) -> &'static str {
+        "use <item>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"use - Use an item
+
+SYNOPSIS
+    use <item>
+
+DESCRIPTION
+    Use up one of the items you carry and apply its effects.
+    Use the "inventory" command to see what you have.
+
+EXAMPLES
+    use coffee
+        Drink a cup of coffee.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        if argv.len() < 2 {
+            return Err(format!("Usage: {}", self.synopsis()));
+        }
+        let name = argv[1..].join(" ");
+        let Some(id) = game_state.inventory.find(&name) else {
+            return Err(format!("You don't have {}", name));
+        };
+        // using equipment means wearing it
+        if game_state
+            .inventory
+            .get(&id)
+            .and_then(|item| item.slot)
+            .is_some()
+        {
+            return EquipCommand.execute(game_state, &["equip", id.as_str()]);
+        }
+        let effects = match game_state.inventory.get(&id) {
+            Some(item) if !item.effects.is_empty() => item.effects.clone(),
+            _ => {
+                return Err(format!(
+                    "{} can't be used",
+                    game_state.inventory.item_name(&id)
+                ))
+            }
+        };
+        game_state.inventory.remove(&id);
+
+        let mut line = vec![commands::Span::plain(format!(
+            "Used {}: ",
+            game_state.inventory.item_name(&id)
+        ))];
+        for (idx, effect) in effects.iter().enumerate() {
+            if idx > 0 {
+                line.push(commands::Span::plain(", "));
+            }
+            line.push(commands::Span::styled(
+                effect.apply(game_state),
+                commands::SpanStyle::Success,
+            ));
+        }
+        Ok(commands::CommandOutput::new().line(line))
+    }
+}
+
+pub struct DropCommand;
+
+impl commands::GameCommand for DropCommand {
+    fn synopsis(&self) -> &'static str {
+        "drop <item>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"drop - Drop an item
+
+SYNOPSIS
+    drop <item>
+
+DESCRIPTION
+    Put one of the items you carry on the ground where you are standing.
+    Walk back onto the tile to pick it up again.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        if argv.len() < 2 {
+            return Err(format!("Usage: {}", self.synopsis()));
+        }
+        let name = argv[1..].join(" ");
+        let Some(id) = game_state.inventory.find(&name) else {
+            return Err(format!("You don't have {}", name));
+        };
+        if game_state.in_battle {
+            return Err("You can't drop items right now".to_string());
+        }
+        game_state.inventory.remove(&id);
+        // dropping the last one takes it off
+        game_state.clamp_hitpoints();
+        game_state.dropped_items.push(id.to_owned());
+        Ok(commands::CommandOutput::text(&format!(
+            "Dropped {}",
+            game_state.inventory.item_name(&id)
+        )))
+    }
+}
+
+pub struct GiveCommand;
+
+impl commands::GameCommand for GiveCommand {
+    fn synopsis(&self) -> &'static str {
+        "give <npc> <item>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"give - Give an item to someone
+
+SYNOPSIS
+    give <npc> <item>
+
+DESCRIPTION
+    Give one of the items you carry to the creature you are talking to.
+    Some of them might like it. Some of them might not.
+
+EXAMPLES
+    give alice coffee
+        Give a cup of coffee to alice.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        if argv.len() < 3 {
+            return Err(format!("Usage: {}", self.synopsis()));
+        }
+        let npc = argv[1].to_lowercase();
+        if game_state.encountered_npc.as_deref() != Some(npc.as_str()) {
+            return Err(format!("{} is not here", argv[1]));
+        }
+        let name = argv[2..].join(" ");
+        let Some(id) = game_state.inventory.find(&name) else {
+            return Err(format!("You don't have {}", name));
+        };
+        game_state.inventory.remove(&id);
+        game_state.clamp_hitpoints();
+        game_state
+            .action_queue
+            .push(npcs::PlayerAction::Give(id.to_owned()));
+        Ok(commands::CommandOutput::text(&format!(
+            "Gave {} to {}",
+            game_state.inventory.item_name(&id),
+            argv[1]
+        )))
+    }
+}
+
+pub struct EquipCommand;
+
+impl commands::GameCommand for EquipCommand {
+    fn synopsis(&self) -> &'static str {
+        "equip [item]"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"equip - Equip an item
+
+SYNOPSIS
+    equip [item]
+
+DESCRIPTION
+    Put on a weapon, armor or accessory you carry. Equipped items increase
+    your stats as long as they are worn. Each slot holds one item, the item
+    previously in the same slot is unequipped.
+    If `item' is omitted, show what you are wearing.
+
+EXAMPLES
+    equip keyboard
+        Wield a mechanical keyboard as your weapon.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        if argv.len() < 2 {
+            let inventory = &game_state.inventory;
+            let rows = inventory::EquipSlot::ALL
+                .into_iter()
+                .map(|slot| {
+                    let item = match inventory.equipped(slot) {
+                        Some(id) => commands::Span::link(
+                            inventory.item_name(id),
+                            format!("unequip {}", slot.name()),
+                        ),
+                        None => commands::Span::styled("-", commands::SpanStyle::Muted),
+                    };
+                    vec![commands::Span::plain(slot.name()), item]
+                })
+                .collect();
+            return Ok(commands::CommandOutput::new().table(&["SLOT", "ITEM"], rows));
+        }
+        let name = argv[1..].join(" ");
+        let Some(id) = game_state.inventory.find(&name) else {
+            return Err(format!("You don't have {}", name));
+        };
+        let previous = game_state.inventory.equip(&id)?;
+        // the item that was taken off might have given more max HP
+        game_state.clamp_hitpoints();
+        let mut line = vec![commands::Span::plain(format!(
+            "Equipped {}",
+            game_state.inventory.item_name(&id)
+        ))];
+        if let Some(previous) = previous.filter(|previous| *previous != id) {
+            line.push(commands::Span::styled(
+                format!(
+                    " (unequipped {})",
+                    game_state.inventory.item_name(&previous)
+                ),
+                commands::SpanStyle::Muted,
+            ));
+        }
+        Ok(commands::CommandOutput::new().line(line))
+    }
+}
+
+pub struct UnequipCommand;
+
+impl commands::GameCommand for UnequipCommand {
+    fn synopsis(&self) -> &'static str {
+        "unequip <slot>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"unequip - Take off an item
+
+SYNOPSIS
+    unequip <slot>
+
+DESCRIPTION
+    Take off the item in the given slot. The slot is one of `weapon',
+    `armor' or `accessory'. The item stays in your inventory.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        let Some(slot_name) = argv.get(1) else {
+            return Err(format!("Usage: {}", self.synopsis()));
+        };
+        let Some(slot) = inventory::EquipSlot::from_name(slot_name) else {
+            return Err(format!("No such slot: {}", slot_name));
+        };
+        match game_state.inventory.unequip(slot) {
+            Some(id) => {
+                game_state.clamp_hitpoints();
+                Ok(commands::CommandOutput::text(&format!(
+                    "Unequipped {}",
+                    game_state.inventory.item_name(&id)
+                )))
+            }
+            None => Err(format!("Nothing is equipped as {}", slot.name())),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{commands, game_backend};
+
+// what a job reports after being updated for a frame
+pub enum JobStatus {
+    Running(Option<commands::CommandOutput>),
+    Finished(commands::CommandOutput),
+}
+
+// a command that keeps running over multiple frames
+pub trait CommandJob: Sync + Send {
+    // called every frame with the number of seconds since the last update
+    fn update(&mut self, game_state: &mut game_backend::GameState, delta: f32) -> JobStatus;
+    // a number between 0 and 1, or None if the progress is unknown
+    fn progress(&self) -> Option<f32> {
+        None
+    }
+}
+
+pub struct Job {
+    pub id: usize,
+    pub command: String,
+    pub background: bool,
+    task: Box<dyn CommandJob>,
+}
+
+// every job that is currently running
+#[derive(Default)]
+pub struct JobTable {
+    jobs: Vec<Job>,
+    next_id: usize,
+}
+
+impl Job {
+    pub fn progress(&self) -> Option<f32> {
+        self.task.progress()
+    }
+}
+
+impl JobTable {
+    pub fn spawn(&mut self, command: &str, background: bool, task: Box<dyn CommandJob>) -> usize {
+        self.next_id += 1;
+        self.jobs.push(Job {
+            id: self.next_id,
+            command: command.to_string(),
+            background,
+            task,
+        });
+        self.next_id
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.iter()
+    }
+    pub fn foreground(&self) -> Option<&Job> {
+        self.jobs.iter().find(|job| !job.background)
+    }
+    pub fn kill(&mut self, id: usize) -> Option<Job> {
+        let index = self.jobs.iter().position(|job| job.id == id)?;
+        Some(self.jobs.remove(index))
+    }
+    // stops the foreground job, like pressing ctrl+c in a shell
+    pub fn interrupt(&mut self) -> Option<Job> {
+        let index = self.jobs.iter().position(|job| !job.background)?;
+        Some(self.jobs.remove(index))
+    }
+    // updates every job, returns the outputs produced in this frame
+    pub fn update(
+        &mut self,
+        game_state: &mut game_backend::GameState,
+        delta: f32,
+    ) -> Vec<commands::CommandOutput> {
+        let mut outputs = vec![];
+        let mut finished = vec![];
+        for job in self.jobs.iter_mut() {
+            match job.task.update(game_state, delta) {
+                JobStatus::Running(output) => outputs.extend(output),
+                JobStatus::Finished(output) => {
+                    outputs.push(output);
+                    if job.background {
+                        outputs.push(commands::CommandOutput::new().line(vec![
+                            commands::Span::styled(
+                                format!("[{}] Done    {}", job.id, job.command),
+                                commands::SpanStyle::Muted,
+                            ),
+                        ]));
+                    }
+                    finished.push(job.id);
+                }
+            }
+        }
+        self.jobs.retain(|job| !finished.contains(&job.id));
+        outputs.retain(|output| !output.blocks.is_empty());
+        outputs
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{commands, game_backend};
+
+pub struct JobsCommand;
+
+impl commands::GameCommand for JobsCommand {
+    fn synopsis(&self) -> &'static str {
+        "jobs"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"jobs - List running jobs
+
+SYNOPSIS
+    jobs
+
+DESCRIPTION
+    Show the commands that are still running, either in the foreground or
+    in the background.
+    A command runs in the background if it ends with `&'.
+
+EXAMPLES
+    scan 5 &
+        Start a scan in the background, then use `jobs' to see its progress.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        _argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        if game_state.jobs.iter().next().is_none() {
+            return Ok(
+                commands::CommandOutput::new().line(vec![commands::Span::styled(
+                    "No jobs running",
+                    commands::SpanStyle::Muted,
+                )]),
+            );
+        }
+        let rows = game_state
+            .jobs
+            .iter()
+            .map(|job| {
+                let progress = match job.progress() {
+                    Some(progress) => format!("{:>3.0}%", progress * 100.0),
+                    None => "-".to_string(),
+                };
+                let state = if job.background {
+                    "Background"
+                } else {
+                    "Foreground"
+                };
+                vec![
+                    commands::Span::link(format!("[{}]", job.id), format!("kill {}", job.id)),
+                    commands::Span::plain(state),
+                    commands::Span::plain(progress),
+                    commands::Span::styled(&job.command, commands::SpanStyle::Code),
+                ]
+            })
+            .collect();
+        Ok(commands::CommandOutput::new().table(&["ID", "STATE", "PROGRESS", "COMMAND"], rows))
+    }
+}
+
+pub struct KillCommand;
+
+impl commands::GameCommand for KillCommand {
+    fn synopsis(&self) -> &'static str {
+        "kill <job_id>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"kill - Stop a running job
+
+SYNOPSIS
+    kill <job_id>
+
+DESCRIPTION
+    Stop the job with the given ID. Use the `jobs' command to find the IDs
+    of running jobs. The foreground job can also be stopped by pressing
+    Ctrl+C in the terminal.
+
+EXAMPLES
+    kill 1
+        Stop the job with ID 1.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        let Some(job_id) = argv.get(1) else {
+            return Err(format!("Usage: {}", self.synopsis()));
+        };
+        let id = match job_id.trim_start_matches('%').parse::<usize>() {
+            Ok(id) => id,
+            Err(_) => return Err(format!("`{}' is not a valid job ID", job_id)),
+        };
+        match game_state.jobs.kill(id) {
+            Some(job) => Ok(
+                commands::CommandOutput::new().line(vec![commands::Span::styled(
+                    format!("[{}] Killed  {}", job.id, job.command),
+                    commands::SpanStyle::Muted,
+                )]),
+            ),
+            None => Err(format!("No such job: {}", id)),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{commands, game_backend};
+
+pub struct LogCommand;
+
+impl commands::GameCommand for LogCommand {
+    fn synopsis(&self) -> &'static str {
+        "log export"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"log - Keep a record of the dialogue
+
+SYNOPSIS
+    log export
+
+DESCRIPTION
+    Save everything that has been said to a text file, or download it
+    when playing in the browser. The same lines can be read and searched
+    in the dialogue tab.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        _game_state: &mut game_backend::GameState,
+        _argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        Err("log is handled by the interface".to_string())
+    }
+    fn spawn(
+        &self,
+        _game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::Execution, String> {
+        match argv.get(1).copied() {
+            Some("export") => Ok(commands::Execution::ExportLog),
+            Some(arg) => Err(format!("Unknown argument: {}", arg)),
+            None => Err(format!("Usage: {}", self.synopsis())),
+        }
+    }
+}
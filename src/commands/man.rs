@@ -29,13 +29,16 @@ EXAMPLES
         &self,
         game_state: &mut game_backend::GameState,
         argv: &[&str],
-    ) -> Result<String, String> {
+    ) -> Result<commands::CommandOutput, String> {
         if let Some(command_name) = argv.get(1) {
             if let Some(command_box) = commands::get_command_by_name(command_name) {
-                if game_state.player_level < command_box.required_level() {
+                if game_state.effective_level() < command_box.required_level() {
                     Err("You don't have access to that command".to_string())
                 } else {
-                    Ok(command_box.man_page().to_string())
+                    let mut output = man_page_output(command_box.man_page());
+                    let name = command_box.synopsis().split_whitespace().next();
+                    output.man_page = name.map(str::to_string);
+                    Ok(output)
                 }
             } else {
                 Err(format!("No such command: {}", command_name))
@@ -45,3 +48,17 @@ EXAMPLES
         }
     }
 }
+
+pub fn man_page_output(page: &str) -> commands::CommandOutput {
+    // section titles are the unindented lines
+    let mut output = commands::CommandOutput::new();
+    for line in page.lines() {
+        let style = if line.starts_with(char::is_uppercase) && line == line.to_uppercase() {
+            commands::SpanStyle::Strong
+        } else {
+            commands::SpanStyle::Code
+        };
+        output = output.line(vec![commands::Span::styled(line, style)]);
+    }
+    output
+}
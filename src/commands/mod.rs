@@ -1,9 +1,29 @@
+mod cheats;
 mod commands;
 mod fireball;
 mod help;
+mod items;
+pub mod jobs;
+mod jobs_commands;
+mod log;
 mod man;
+pub mod output;
+mod scan;
+mod sleep;
+mod sudo;
 
 use crate::game_backend;
+pub use cheats::Cheat;
+pub use man::man_page_output;
+pub use output::{CommandOutput, Span, SpanStyle};
+
+// a command either finishes immediately or keeps running as a job
+pub enum Execution {
+    Output(CommandOutput),
+    Job(Box<dyn jobs::CommandJob>),
+    // the dialogue is kept by the ui, which writes it out
+    ExportLog,
+}
 
 pub trait GameCommand {
     fn synopsis(&self) -> &'static str;
@@ -13,7 +33,15 @@ pub trait GameCommand {
         &self,
         game_state: &mut game_backend::GameState,
         argv: &[&str],
-    ) -> Result<String, String>;
+    ) -> Result<CommandOutput, String>;
+    // long-running commands override this to return a job instead
+    fn spawn(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<Execution, String> {
+        self.execute(game_state, argv).map(Execution::Output)
+    }
 }
 
 pub struct InvalidCommand;
@@ -32,12 +60,37 @@ impl GameCommand for InvalidCommand {
         &self,
         _game_state: &mut game_backend::GameState,
         argv: &[&str],
-    ) -> Result<String, String> {
-        Err(format!("Invalid command: {}", argv[0]))
+    ) -> Result<CommandOutput, String> {
+        Err(format!("Invalid command: {}", argv.first().unwrap_or(&"")))
     }
 }
 
-const COMMAND_LIST: [&'static str; 4] = ["commands", "help", "man", "fireball"];
+const COMMAND_LIST: [&'static str; 23] = [
+    "commands",
+    "help",
+    "man",
+    "fireball",
+    "jobs",
+    "kill",
+    "scan",
+    "sleep",
+    "inventory",
+    "use",
+    "drop",
+    "give",
+    "equip",
+    "unequip",
+    "sudo",
+    "log",
+    // only available with the `debug` feature
+    "setlevel",
+    "teleport",
+    "setprogress",
+    "spawn",
+    "heal",
+    "skipcg",
+    "god",
+];
 
 pub fn get_command_by_name(name: &str) -> Option<Box<dyn GameCommand>> {
     let name = name.trim();
@@ -46,6 +99,26 @@ pub fn get_command_by_name(name: &str) -> Option<Box<dyn GameCommand>> {
         "help" => Some(Box::new(help::HelpCommand)),
         "man" | "manual" => Some(Box::new(man::ManCommand)),
         "fireball" => Some(Box::new(fireball::FireballCommand)),
+        "jobs" => Some(Box::new(jobs_commands::JobsCommand)),
+        "kill" => Some(Box::new(jobs_commands::KillCommand)),
+        "scan" => Some(Box::new(scan::ScanCommand)),
+        "sleep" => Some(Box::new(sleep::SleepCommand)),
+        "inventory" | "inv" => Some(Box::new(items::InventoryCommand)),
+        "use" => Some(Box::new(items::UseCommand)),
+        "drop" => Some(Box::new(items::DropCommand)),
+        "give" => Some(Box::new(items::GiveCommand)),
+        "equip" => Some(Box::new(items::EquipCommand)),
+        "unequip" => Some(Box::new(items::UnequipCommand)),
+        "sudo" => Some(Box::new(sudo::SudoCommand)),
+        "log" => Some(Box::new(log::LogCommand)),
+        _ if !cfg!(feature = "debug") => None,
+        "setlevel" => Some(Box::new(cheats::SetLevelCommand)),
+        "teleport" | "tp" => Some(Box::new(cheats::TeleportCommand)),
+        "setprogress" => Some(Box::new(cheats::SetProgressCommand)),
+        "spawn" => Some(Box::new(cheats::SpawnCommand)),
+        "heal" => Some(Box::new(cheats::HealCommand)),
+        "skipcg" => Some(Box::new(cheats::SkipCgCommand)),
+        "god" => Some(Box::new(cheats::GodCommand)),
         _ => None,
     }
 }
@@ -53,20 +126,20 @@ pub fn get_command_by_name(name: &str) -> Option<Box<dyn GameCommand>> {
 pub fn execute_command(
     game_state: &mut game_backend::GameState,
     command: &str,
-) -> Result<String, String> {
+) -> Result<Execution, String> {
     let argv = command
         .split_whitespace()
         .filter(|&s| !s.is_empty())
         .collect::<Vec<_>>();
     let command_name = argv.first().unwrap_or(&"");
     let command_box = get_command_by_name(command_name).unwrap_or(Box::new(InvalidCommand));
-    if game_state.player_level < command_box.required_level() {
+    if game_state.effective_level() < command_box.required_level() {
         Err(
             "You do not have access to run that command.\nThis incident will be reported."
                 .to_string(),
         )
     } else {
-        command_box.execute(game_state, &argv)
+        command_box.spawn(game_state, &argv)
     }
 }
 
@@ -74,7 +147,12 @@ pub fn list_commands(prefix: &str, level: i32) -> Vec<&str> {
     COMMAND_LIST
         .into_iter()
         .filter(|s| {
-            s.starts_with(prefix) && get_command_by_name(s).unwrap().required_level() <= level
+            // cheats are listed but don't exist without the `debug` feature
+            let is_available = match get_command_by_name(s) {
+                Some(command) => command.required_level() <= level,
+                None => false,
+            };
+            s.starts_with(prefix) && is_available
         })
         .collect()
 }
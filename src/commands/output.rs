@@ -0,0 +1,165 @@
+use std::fmt;
+
+// how serious a command's output is, affects the color of unstyled text
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpanStyle {
+    #[default]
+    Plain,
+    Strong,
+    Code,
+    Muted,
+    Success,
+    Warning,
+    Error,
+}
+
+// a piece of text with a single style
+// if `link` is set, clicking on the text runs it as a command
+#[derive(Clone, Default)]
+pub struct Span {
+    pub text: String,
+    pub style: SpanStyle,
+    pub link: Option<String>,
+}
+
+#[derive(Clone)]
+pub enum Block {
+    Line(Vec<Span>),
+    Table {
+        header: Vec<String>,
+        rows: Vec<Vec<Span>>,
+    },
+}
+
+// the structured result of running a command
+#[derive(Clone, Default)]
+pub struct CommandOutput {
+    pub severity: Severity,
+    pub blocks: Vec<Block>,
+    // the command whose manual this is, so that the ui can show it in the player's language
+    pub man_page: Option<String>,
+}
+
+impl Span {
+    pub fn plain(text: impl Into<String>) -> Span {
+        Span::styled(text, SpanStyle::Plain)
+    }
+    pub fn styled(text: impl Into<String>, style: SpanStyle) -> Span {
+        Span {
+            text: text.into(),
+            style,
+            link: None,
+        }
+    }
+    pub fn link(text: impl Into<String>, command: impl Into<String>) -> Span {
+        Span {
+            text: text.into(),
+            style: SpanStyle::Code,
+            link: Some(command.into()),
+        }
+    }
+}
+
+impl CommandOutput {
+    pub fn new() -> CommandOutput {
+        CommandOutput::default()
+    }
+    // plain text, one line block per line
+    pub fn text(text: &str) -> CommandOutput {
+        CommandOutput::new().lines(text, SpanStyle::Plain)
+    }
+    pub fn error(text: &str) -> CommandOutput {
+        CommandOutput::new()
+            .with_severity(Severity::Error)
+            .lines(text, SpanStyle::Error)
+    }
+    pub fn with_severity(mut self, severity: Severity) -> CommandOutput {
+        self.severity = severity;
+        self
+    }
+    pub fn line(mut self, spans: Vec<Span>) -> CommandOutput {
+        self.blocks.push(Block::Line(spans));
+        self
+    }
+    pub fn lines(mut self, text: &str, style: SpanStyle) -> CommandOutput {
+        for line in text.lines() {
+            self.blocks
+                .push(Block::Line(vec![Span::styled(line, style)]));
+        }
+        self
+    }
+    pub fn table(mut self, header: &[&str], rows: Vec<Vec<Span>>) -> CommandOutput {
+        self.blocks.push(Block::Table {
+            header: header.iter().map(|s| s.to_string()).collect(),
+            rows,
+        });
+        self
+    }
+    // the output without any styling, used for logs
+    pub fn plain_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl From<String> for CommandOutput {
+    fn from(text: String) -> Self {
+        CommandOutput::text(&text)
+    }
+}
+
+impl From<&str> for CommandOutput {
+    fn from(text: &str) -> Self {
+        CommandOutput::text(text)
+    }
+}
+
+impl fmt::Display for CommandOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines: Vec<String> = vec![];
+        for block in self.blocks.iter() {
+            match block {
+                Block::Line(spans) => {
+                    lines.push(spans.iter().map(|span| span.text.as_str()).collect());
+                }
+                Block::Table { header, rows } => {
+                    // pad every column to the width of its longest cell
+                    let mut widths: Vec<usize> = header.iter().map(|s| s.chars().count()).collect();
+                    for row in rows.iter() {
+                        for (idx, cell) in row.iter().enumerate() {
+                            let width = cell.text.chars().count();
+                            match widths.get_mut(idx) {
+                                Some(w) => *w = (*w).max(width),
+                                None => widths.push(width),
+                            }
+                        }
+                    }
+                    let format_row = |cells: Vec<&str>| {
+                        cells
+                            .iter()
+                            .zip(widths.iter())
+                            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                            .collect::<Vec<_>>()
+                            .join("  ")
+                            .trim_end()
+                            .to_string()
+                    };
+                    if !header.is_empty() {
+                        lines.push(format_row(header.iter().map(|s| s.as_str()).collect()));
+                    }
+                    for row in rows.iter() {
+                        lines.push(format_row(row.iter().map(|s| s.text.as_str()).collect()));
+                    }
+                }
+            }
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{commands, game_backend};
+
+pub struct ScanCommand;
+
+impl ScanCommand {
+    const DEFAULT_SECTORS: usize = 8;
+    const MAX_SECTORS: usize = 64;
+    const SECONDS_PER_SECTOR: f32 = 0.4;
+}
+
+impl commands::GameCommand for ScanCommand {
+    fn synopsis(&self) -> &'static str {
+        "scan [sectors]"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"scan - Scan the system for anomalies
+
+SYNOPSIS
+    scan [sectors]
+
+DESCRIPTION
+    Scan the sectors around your location for bugs and other anomalies.
+    Each sector takes a while to scan, the results are shown as soon as
+    they are available.
+    If `sectors' is omitted, scan 8 sectors. At most 64 sectors can be
+    scanned at a time.
+
+    Press Ctrl+C to stop the scan, or add `&' after the command to run it
+    in the background.
+
+EXAMPLES
+    scan 16 &
+        Scan 16 sectors in the background.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        _game_state: &mut game_backend::GameState,
+        _argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        Err("scan must be run as a job".to_string())
+    }
+    fn spawn(
+        &self,
+        game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::Execution, String> {
+        let sectors = if let Some(sectors) = argv.get(1) {
+            match sectors.parse::<usize>() {
+                Ok(sectors) if 0 < sectors && sectors <= ScanCommand::MAX_SECTORS => sectors,
+                Ok(_) => {
+                    return Err(format!(
+                        "Number of sectors should be between 1 and {}",
+                        ScanCommand::MAX_SECTORS
+                    ))
+                }
+                Err(_) => return Err(format!("`{}' is not a valid number", sectors)),
+            }
+        } else {
+            ScanCommand::DEFAULT_SECTORS
+        };
+        Ok(commands::Execution::Job(Box::new(ScanJob {
+            origin: (game_state.player_x, game_state.player_y),
+            sectors,
+            scanned: 0,
+            anomalies: 0,
+            elapsed: 0.0,
+        })))
+    }
+}
+
+struct ScanJob {
+    origin: (usize, usize),
+    sectors: usize,
+    scanned: usize,
+    anomalies: usize,
+    elapsed: f32,
+}
+
+impl commands::jobs::CommandJob for ScanJob {
+    fn update(
+        &mut self,
+        game_state: &mut game_backend::GameState,
+        delta: f32,
+    ) -> commands::jobs::JobStatus {
+        self.elapsed += delta;
+        if self.elapsed < ScanCommand::SECONDS_PER_SECTOR {
+            return commands::jobs::JobStatus::Running(None);
+        }
+        self.elapsed -= ScanCommand::SECONDS_PER_SECTOR;
+        self.scanned += 1;
+
+        // whether a sector has anomalies only depends on where the scan started
+        let (x, y) = self.origin;
+        let hash = x.wrapping_mul(73856093)
+            ^ y.wrapping_mul(19349663)
+            ^ self.scanned.wrapping_mul(83492791);
+        let sector = commands::Span::plain(format!("sector {}/{}: ", self.scanned, self.sectors));
+        let line = if hash % 5 == 0 {
+            self.anomalies += 1;
+            vec![
+                sector,
+                commands::Span::styled("anomaly", commands::SpanStyle::Warning),
+            ]
+        } else {
+            vec![
+                sector,
+                commands::Span::styled("clean", commands::SpanStyle::Success),
+            ]
+        };
+        let mut output = commands::CommandOutput::new().line(line);
+
+        if self.scanned < self.sectors {
+            return commands::jobs::JobStatus::Running(Some(output));
+        }
+        output = output.line(vec![commands::Span::styled(
+            format!(
+                "Scan complete: {} sector(s), {} {}",
+                self.sectors,
+                self.anomalies,
+                if self.anomalies == 1 { "anomaly" } else { "anomalies" }
+            ),
+            commands::SpanStyle::Strong,
+        )]);
+        if game_state.in_battle {
+            output = output.line(vec![commands::Span::styled(
+                "Hostile process detected at your location!",
+                commands::SpanStyle::Error,
+            )]);
+        }
+        commands::jobs::JobStatus::Finished(output)
+    }
+    fn progress(&self) -> Option<f32> {
+        Some(self.scanned as f32 / self.sectors as f32)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{commands, game_backend};
+
+pub struct SleepCommand;
+
+impl commands::GameCommand for SleepCommand {
+    fn synopsis(&self) -> &'static str {
+        "sleep <seconds>"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"sleep - Wait for some time
+
+SYNOPSIS
+    sleep <seconds>
+
+DESCRIPTION
+    Do nothing for the given number of seconds.
+    Even sinners need some rest.
+
+EXAMPLES
+    sleep 10 &
+        Sleep for 10 seconds in the background.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        i32::MIN
+    }
+    fn execute(
+        &self,
+        _game_state: &mut game_backend::GameState,
+        _argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        Err("sleep must be run as a job".to_string())
+    }
+    fn spawn(
+        &self,
+        _game_state: &mut game_backend::GameState,
+        argv: &[&str],
+    ) -> Result<commands::Execution, String> {
+        let Some(seconds) = argv.get(1) else {
+            return Err(format!("Usage: {}", self.synopsis()));
+        };
+        match seconds.parse::<f32>() {
+            Ok(duration) if duration >= 0.0 && duration.is_finite() => {
+                Ok(commands::Execution::Job(Box::new(SleepJob {
+                    duration,
+                    elapsed: 0.0,
+                })))
+            }
+            _ => Err(format!("`{}' is not a valid number of seconds", seconds)),
+        }
+    }
+}
+
+struct SleepJob {
+    duration: f32,
+    elapsed: f32,
+}
+
+impl commands::jobs::CommandJob for SleepJob {
+    fn update(
+        &mut self,
+        _game_state: &mut game_backend::GameState,
+        delta: f32,
+    ) -> commands::jobs::JobStatus {
+        self.elapsed += delta;
+        if self.elapsed >= self.duration {
+            commands::jobs::JobStatus::Finished(commands::CommandOutput::new())
+        } else {
+            commands::jobs::JobStatus::Running(None)
+        }
+    }
+    fn progress(&self) -> Option<f32> {
+        if self.duration > 0.0 {
+            Some((self.elapsed / self.duration).min(1.0))
+        } else {
+            Some(1.0)
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{commands, game_backend, status_effects};
+
+pub struct SudoCommand;
+
+impl SudoCommand {
+    const LEVEL_BOOST: i32 = 2;
+    const DURATION: f32 = 30.0;
+}
+
+impl commands::GameCommand for SudoCommand {
+    fn synopsis(&self) -> &'static str {
+        "sudo"
+    }
+    fn man_page(&self) -> &'static str {
+        r#"sudo - Become root for a while
+
+SYNOPSIS
+    sudo
+
+DESCRIPTION
+    Temporarily raise your access level by 2 for 30 seconds, unlocking more
+    powerful commands. Root access can't be extended while it is active.
+
+    We trust you have received the usual lecture from the local System
+    Administrator. It usually boils down to these three things:
+        #1) Respect the privacy of others.
+        #2) Think before you type.
+        #3) With great power comes great responsibility.
+"#
+    }
+    fn required_level(&self) -> i32 {
+        1
+    }
+    fn execute(
+        &self,
+        game_state: &mut game_backend::GameState,
+        _argv: &[&str],
+    ) -> Result<commands::CommandOutput, String> {
+        if game_state
+            .player_effects
+            .has(status_effects::EffectKind::RootAccess)
+        {
+            return Err("You already have root access".to_string());
+        }
+        game_state
+            .player_effects
+            .apply(status_effects::StatusEffect::new(
+                status_effects::EffectKind::RootAccess,
+                status_effects::EffectDuration::Seconds(SudoCommand::DURATION),
+                SudoCommand::LEVEL_BOOST,
+            ));
+        Ok(commands::CommandOutput::new()
+            .line(vec![
+                commands::Span::plain("Access level "),
+                commands::Span::styled(
+                    format!("+{}", SudoCommand::LEVEL_BOOST),
+                    commands::SpanStyle::Success,
+                ),
+                commands::Span::plain(format!(" for {} seconds", SudoCommand::DURATION)),
+            ])
+            .line(vec![commands::Span::styled(
+                "This incident will be reported.",
+                commands::SpanStyle::Muted,
+            )]))
+    }
+}
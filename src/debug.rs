@@ -0,0 +1,177 @@
+use crate::{app_state::AppState, commands, game_backend, game_map, loading};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        if !cfg!(feature = "debug") {
+            return;
+        }
+        app.init_resource::<DebugState>()
+            .add_system(apply_cheats)
+            .add_system(god_mode)
+            .add_system(toggle_overlay)
+            .add_system(debug_overlay)
+            .add_system_set(SystemSet::on_update(AppState::Cutscene).with_system(skip_cgs));
+    }
+}
+
+// carries out what cheat commands asked for
+fn apply_cheats(
+    map: Res<game_map::Map>,
+    mut game_state: ResMut<game_backend::GameState>,
+    mut npcs: ResMut<game_backend::Npcs>,
+    mut debug_state: ResMut<DebugState>,
+    mut result_events: EventWriter<game_backend::CommandResultEvent>,
+    mut reload_events: EventWriter<loading::ReloadEvent>,
+) {
+    if game_state.cheats.is_empty() {
+        return;
+    }
+    for cheat in std::mem::take(&mut game_state.cheats) {
+        match cheat {
+            commands::Cheat::Teleport(tile) => {
+                if map.is_road(tile) {
+                    (game_state.player_x, game_state.player_y) = tile;
+                } else {
+                    let msg = format!("Error: ({}, {}) is not a road", tile.0, tile.1);
+                    let output = commands::CommandOutput::error(&msg);
+                    result_events.send(game_backend::CommandResultEvent(output));
+                }
+            }
+            commands::Cheat::SpawnNpc(id) => {
+                // the encounter starts as soon as the npc is on the player's tile
+                let location = (game_state.player_x, game_state.player_y);
+                let npc = npcs.npcs.entry(id.to_owned()).or_default();
+                npc.location = location;
+                game_state.completed_npcs.retain(|completed| *completed != id);
+                reload_events.send(loading::ReloadEvent::Npcs);
+            }
+            commands::Cheat::SkipCgs(skip) => debug_state.skip_cgs = skip,
+        }
+    }
+}
+
+fn god_mode(mut game_state: ResMut<game_backend::GameState>) {
+    let max_hp = game_state.effective_max_hp();
+    if game_state.god_mode && game_state.player_hitpoints != max_hp {
+        game_state.player_hitpoints = max_hp;
+    }
+}
+
+fn skip_cgs(debug_state: Res<DebugState>, mut skip_events: EventWriter<game_backend::SkipCgEvent>) {
+    if debug_state.skip_cgs {
+        skip_events.send(game_backend::SkipCgEvent);
+    }
+}
+
+// f3 shows and hides the overlay
+fn toggle_overlay(keyboard_input: Res<Input<KeyCode>>, mut debug_state: ResMut<DebugState>) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        debug_state.show_overlay = !debug_state.show_overlay;
+    }
+}
+
+fn debug_overlay(
+    mut egui_context: ResMut<EguiContext>,
+    windows: Res<Windows>,
+    app_state: Res<State<AppState>>,
+    map: Res<game_map::Map>,
+    game_state: Res<game_backend::GameState>,
+    active_npc: Res<game_backend::ActiveNpc>,
+    debug_state: Res<DebugState>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+) {
+    if !debug_state.show_overlay {
+        return;
+    }
+    let ctx = egui_context.ctx_mut();
+
+    egui::Window::new("Debug")
+        .default_pos((10.0, 300.0))
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("game_state").num_columns(2).show(ui, |ui| {
+                let mut row = |name: &str, value: String| {
+                    ui.label(name);
+                    ui.monospace(value);
+                    ui.end_row();
+                };
+                row("State", format!("{:?}", app_state.current()));
+                row("Progress", format!("{:?}", game_state.game_progress));
+                row("Tile", format!("({}, {})", game_state.player_x, game_state.player_y));
+                row(
+                    "Level",
+                    format!("{} ({})", game_state.player_level, game_state.effective_level()),
+                );
+                row(
+                    "HP",
+                    format!("{}/{}", game_state.player_hitpoints, game_state.effective_max_hp()),
+                );
+                row(
+                    "ATK/DEF",
+                    format!("{}/{}", game_state.player_atk, game_state.player_def),
+                );
+                row("God mode", game_state.god_mode.to_string());
+                row("Skip cutscenes", debug_state.skip_cgs.to_string());
+                row("Completed", game_state.completed_npcs.join(", "));
+            });
+
+            ui.separator();
+            let Some(npc) = active_npc.0.as_ref() else {
+                ui.label("No active npc");
+                return;
+            };
+            egui::Grid::new("active_npc").num_columns(2).show(ui, |ui| {
+                let mut row = |name: &str, value: String| {
+                    ui.label(name);
+                    ui.monospace(value);
+                    ui.end_row();
+                };
+                row("NPC", format!("{} ({})", npc.name(), npc.id()));
+                row("HP", format!("{:?}", npc.hitpoints()));
+                row("Job completed", npc.job_completed().to_string());
+                row("Turns", game_state.battle_turns.to_string());
+                row("Last command", format!("{:?}", game_state.last_command));
+                row("Queued actions", game_state.action_queue.len().to_string());
+            });
+        });
+
+    // label every tile on the screen with its coordinates
+    let (Some(window), Ok((transform, projection))) =
+        (windows.get_primary(), camera_query.get_single())
+    else {
+        return;
+    };
+    if !map.loaded || !app_state.current().is_playing() {
+        return;
+    }
+    let size = Vec2::new(window.width(), window.height());
+    let tile_size = Vec2::new(game_map::Tile::WIDTH, game_map::Tile::HEIGHT);
+    let center = transform.translation.truncate();
+    let min = ((center - size / 2.0 * projection.scale) / tile_size).floor().max(Vec2::ZERO);
+    let max = ((center + size / 2.0 * projection.scale) / tile_size).floor();
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    for x in min.x as usize..=(max.x as usize).min(map.width.saturating_sub(1)) {
+        for y in min.y as usize..=(max.y as usize).min(map.height.saturating_sub(1)) {
+            let corner = Vec2::new(x as f32, y as f32 + 1.0) * tile_size;
+            let screen = (corner - center) / projection.scale + size / 2.0;
+            painter.text(
+                egui::pos2(screen.x + 4.0, size.y - screen.y + 4.0),
+                egui::Align2::LEFT_TOP,
+                format!("{}, {}", x, y),
+                egui::FontId::monospace(14.0),
+                egui::Color32::from_rgba_unmultiplied(255, 255, 255, 160),
+            );
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct DebugState {
+    show_overlay: bool,
+    // set by `skipcg`, kept when starting a new game
+    skip_cgs: bool,
+}
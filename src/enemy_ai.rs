@@ -0,0 +1,245 @@
+use crate::npc_response;
+use crate::{app_state::AppState, commands, game_backend, loading, npcs, status_effects};
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct EnemyAiPlugin;
+
+impl Plugin for EnemyAiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<BehaviorFile>::new(&["ai.ron"]))
+            .init_resource::<BehaviorFileHandle>()
+            .init_resource::<Behaviors>()
+            .init_resource::<EnemyState>()
+            .add_startup_system(load_files)
+            .add_system(prepare_behaviors)
+            .add_system_set(SystemSet::on_update(AppState::Battle).with_system(enemy_turn))
+            .add_system_set(SystemSet::on_exit(AppState::Battle).with_system(reset_enemy));
+    }
+}
+
+fn load_files(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut behavior_handle: ResMut<BehaviorFileHandle>,
+) {
+    behavior_handle.0 = loading.load(&asset_server, "behaviors.ai.ron");
+}
+
+fn prepare_behaviors(
+    mut behaviors: ResMut<Behaviors>,
+    behavior_handle: Res<BehaviorFileHandle>,
+    behavior_file: Res<Assets<BehaviorFile>>,
+) {
+    if behaviors.loaded {
+        return;
+    }
+    let Some(behavior_file) = behavior_file.get(&behavior_handle.0) else { return; };
+
+    behaviors.behaviors = behavior_file.behaviors.clone();
+    behaviors.loaded = true;
+}
+
+// lets the encountered npc act after every command in battle, and on a timer if it has one
+fn enemy_turn(
+    time: Res<Time>,
+    behaviors: Res<Behaviors>,
+    mut game_state: ResMut<game_backend::GameState>,
+    mut active_npc: ResMut<game_backend::ActiveNpc>,
+    mut enemy_state: ResMut<EnemyState>,
+    mut result_events: EventWriter<game_backend::CommandResultEvent>,
+    mut response_events: EventWriter<game_backend::NpcResponseEvent>,
+) {
+    let Some(npc) = active_npc.0.as_mut() else { return; };
+    let Some(behavior) = behaviors.behaviors.get(npc.id()) else { return; };
+    if enemy_state.npc.as_deref() != Some(npc.id()) {
+        *enemy_state = EnemyState {
+            npc: Some(npc.id().to_string()),
+            seen_turns: game_state.battle_turns,
+            cooldowns: vec![0; behavior.rules.len()],
+            ..default()
+        };
+    }
+    if npc.job_completed() {
+        return;
+    }
+    if matches!(npc.hitpoints(), Some((hitpoints, _)) if hitpoints <= 0) {
+        return;
+    }
+
+    let mut turns = game_state.battle_turns.saturating_sub(enemy_state.seen_turns);
+    enemy_state.seen_turns = game_state.battle_turns;
+    if let Some(interval) = behavior.turn_interval {
+        enemy_state.timer += time.delta_seconds();
+        if enemy_state.timer >= interval {
+            enemy_state.timer -= interval;
+            turns += 1;
+        }
+    }
+
+    for _ in 0..turns {
+        enemy_state.turns_taken += 1;
+        for cooldown in enemy_state.cooldowns.iter_mut() {
+            *cooldown = cooldown.saturating_sub(1);
+        }
+        if game_state
+            .enemy_effects
+            .has(status_effects::EffectKind::Stun)
+        {
+            let message = format!("{} is stunned and can't move", npc.name());
+            result_events.send(game_backend::CommandResultEvent(
+                commands::CommandOutput::new().lines(&message, commands::SpanStyle::Muted),
+            ));
+            continue;
+        }
+
+        // the first rule whose condition holds and which isn't cooling down is used
+        let Some(index) = (0..behavior.rules.len()).find(|&index| {
+            enemy_state.cooldowns[index] == 0
+                && behavior.rules[index]
+                    .when
+                    .holds(&game_state, &**npc, enemy_state.turns_taken)
+        }) else {
+            continue;
+        };
+        let rule = &behavior.rules[index];
+        enemy_state.cooldowns[index] = rule.cooldown;
+
+        if let Some(taunt) = &rule.taunt {
+            response_events.send(game_backend::NpcResponseEvent(npc_response!(
+                taunt,
+                npc.name()
+            )));
+        }
+        if let Some(message) = rule.action.perform(&mut game_state, &mut **npc) {
+            result_events.send(game_backend::CommandResultEvent(
+                commands::CommandOutput::new()
+                    .with_severity(commands::output::Severity::Warning)
+                    .lines(&message, commands::SpanStyle::Warning),
+            ));
+        }
+    }
+}
+
+fn reset_enemy(mut enemy_state: ResMut<EnemyState>) {
+    *enemy_state = EnemyState::default();
+}
+
+#[derive(Clone, Deserialize)]
+pub enum Condition {
+    Always,
+    // the player's HP is below a fraction of their max HP
+    PlayerHpBelow(f32),
+    // the npc's HP is below a fraction of its max HP
+    SelfHpBelow(f32),
+    PlayerHas(status_effects::EffectKind),
+    PlayerLacks(status_effects::EffectKind),
+    // the player's last command in this battle had this name
+    LastCommand(String),
+    EveryNthTurn(u32),
+}
+
+#[derive(Clone, Deserialize)]
+pub enum AiAction {
+    // attacks the player, reduced by the player's DEF
+    Attack(i32),
+    Heal(i32),
+    // applies a status effect to the player
+    Apply(status_effects::StatusEffect),
+    // does nothing, used with taunts
+    Wait,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Rule {
+    pub when: Condition,
+    pub action: AiAction,
+    // said by the npc when the rule is used
+    #[serde(default)]
+    pub taunt: Option<String>,
+    // the number of turns before the rule can be used again
+    #[serde(default)]
+    pub cooldown: u32,
+}
+
+// how an npc fights, rules are checked in order
+#[derive(Clone, Deserialize)]
+pub struct Behavior {
+    // the npc also acts every this many seconds, even if the player does nothing
+    #[serde(default)]
+    pub turn_interval: Option<f32>,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Deserialize, bevy::reflect::TypeUuid)]
+#[uuid = "d3a1f6e2-6c57-4b8e-9f0a-2e4c7b91d5a8"]
+struct BehaviorFile {
+    behaviors: HashMap<String, Behavior>,
+}
+
+#[derive(Resource, Default)]
+struct BehaviorFileHandle(Handle<BehaviorFile>);
+
+#[derive(Resource, Default)]
+pub struct Behaviors {
+    pub behaviors: HashMap<String, Behavior>,
+    pub loaded: bool,
+}
+
+// what the ai remembers about the current battle
+#[derive(Resource, Default)]
+struct EnemyState {
+    npc: Option<String>,
+    // the value of `battle_turns` the last time the npc acted
+    seen_turns: u32,
+    turns_taken: u32,
+    timer: f32,
+    cooldowns: Vec<u32>,
+}
+
+impl Condition {
+    fn holds(&self, game_state: &game_backend::GameState, npc: &dyn npcs::Npc, turn: u32) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::PlayerHpBelow(fraction) => {
+                (game_state.player_hitpoints as f32)
+                    < fraction * game_state.effective_max_hp() as f32
+            }
+            Condition::SelfHpBelow(fraction) => match npc.hitpoints() {
+                Some((hitpoints, max_hp)) => (hitpoints as f32) < fraction * max_hp as f32,
+                None => false,
+            },
+            Condition::PlayerHas(kind) => game_state.player_effects.has(*kind),
+            Condition::PlayerLacks(kind) => !game_state.player_effects.has(*kind),
+            Condition::LastCommand(name) => game_state.last_command.as_ref() == Some(name),
+            Condition::EveryNthTurn(n) => *n > 0 && turn % n == 0,
+        }
+    }
+}
+
+impl AiAction {
+    // performs the action and describes what happened
+    fn perform(
+        &self,
+        game_state: &mut game_backend::GameState,
+        npc: &mut dyn npcs::Npc,
+    ) -> Option<String> {
+        match self {
+            AiAction::Attack(attack) => {
+                let damage = game_state.damage_player(*attack);
+                Some(format!("{} attacks! You lost {} HP.", npc.name(), damage))
+            }
+            AiAction::Heal(amount) => {
+                npc.heal(*amount);
+                Some(format!("{} recovers {} HP.", npc.name(), amount))
+            }
+            AiAction::Apply(effect) => {
+                game_state.player_effects.apply(*effect);
+                Some(format!("{} inflicts {} on you.", npc.name(), effect.kind.name()))
+            }
+            AiAction::Wait => None,
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{accessibility, loading, locale};
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::text::{Text2dBounds, Text2dSize};
+use bevy_egui::{egui, EguiContext};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct FontsPlugin;
+
+impl Plugin for FontsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<FontSet>()
+            .init_asset_loader::<FontSetLoader>()
+            .init_resource::<Fonts>()
+            .add_startup_system(load_font_set)
+            .add_system(apply_fonts)
+            .add_system(update_captions.after(apply_fonts));
+    }
+}
+
+fn load_font_set(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut fonts: ResMut<Fonts>,
+) {
+    fonts.set = loading.load(&asset_server, "fontfile.fonts.ron");
+}
+
+// hands the fonts to egui and to the text in the game world, again when the file changes or
+// the dyslexia-friendly font is switched
+fn apply_fonts(
+    mut egui_context: ResMut<EguiContext>,
+    mut fonts: ResMut<Fonts>,
+    font_sets: Res<Assets<FontSet>>,
+    accessibility: Res<accessibility::Accessibility>,
+    mut asset_events: EventReader<AssetEvent<FontSet>>,
+    mut applied_dyslexia_font: Local<Option<bool>>,
+) {
+    let modified = asset_events
+        .iter()
+        .any(|event| matches!(event, AssetEvent::Created { .. } | AssetEvent::Modified { .. }));
+    let dyslexia_font = accessibility.dyslexia_font;
+    if !modified && *applied_dyslexia_font == Some(dyslexia_font) {
+        return;
+    }
+    let Some(font_set) = font_sets.get(&fonts.set) else { return; };
+
+    egui_context.ctx_mut().set_fonts(font_set.definitions(dyslexia_font));
+    *applied_dyslexia_font = Some(dyslexia_font);
+    fonts.missing = font_set.missing.to_owned();
+    // the font is loaded with the set, a file that couldn't be read has no handle
+    fonts.text = font_set
+        .text
+        .as_ref()
+        .and_then(|name| font_set.handles.get(name))
+        .cloned();
+}
+
+// keeps captions in the chosen language and font, they get their text once there is a font
+// to draw it with
+fn update_captions(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    locale: Res<locale::Locale>,
+    mut caption_query: Query<(Entity, &Caption, Option<&mut Text>)>,
+) {
+    let Some(font) = fonts.text.as_ref() else { return; };
+    for (entity, caption, text) in caption_query.iter_mut() {
+        let value = locale.tr(&caption.text);
+        let Some(mut text) = text else {
+            let style = TextStyle {
+                font: font.clone(),
+                font_size: caption.font_size,
+                color: caption.color,
+            };
+            commands.entity(entity).insert((
+                Text::from_section(value, style).with_alignment(TextAlignment::CENTER),
+                Text2dSize::default(),
+                Text2dBounds::default(),
+            ));
+            continue;
+        };
+        for section in text.sections.iter_mut() {
+            if section.value != value {
+                section.value = value.to_owned();
+            }
+            if section.style.font != *font {
+                section.style.font = font.clone();
+            }
+        }
+    }
+}
+
+// text in the game world, translated and drawn with the font of `Fonts::text`
+#[derive(Component)]
+pub struct Caption {
+    pub text: String,
+    pub font_size: f32,
+    pub color: Color,
+}
+
+impl Caption {
+    // bevy has no font of its own, the text is added by `update_captions`
+    pub fn bundle(text: &str, font_size: f32, color: Color, transform: Transform) -> impl Bundle {
+        (
+            SpatialBundle::from_transform(transform),
+            Caption {
+                text: text.to_string(),
+                font_size,
+                color,
+            },
+        )
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Fonts {
+    set: Handle<FontSet>,
+    // the font of captions, bevy draws text with a single font so it has to cover every language.
+    // None until the font is loaded, or if the font file is missing
+    pub text: Option<Handle<Font>>,
+    // the font files that couldn't be read, shown on the title screen
+    pub missing: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+enum Family {
+    Proportional,
+    Monospace,
+}
+
+impl Family {
+    fn egui_family(&self) -> egui::FontFamily {
+        match self {
+            Family::Proportional => egui::FontFamily::Proportional,
+            Family::Monospace => egui::FontFamily::Monospace,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FontSetFile {
+    // font files by name
+    fonts: HashMap<String, String>,
+    // the fonts of a family in the order they are tried for each character,
+    // "egui" stands for the fonts egui comes with
+    families: HashMap<Family, Vec<String>>,
+    #[serde(default)]
+    text: Option<String>,
+    // put before the proportional fonts when the accessibility option is on
+    #[serde(default)]
+    dyslexia_friendly: Option<String>,
+}
+
+#[derive(bevy::reflect::TypeUuid)]
+#[uuid = "9b6d2f3e-41c8-4e7a-a5d0-7f2c8e1b3a64"]
+struct FontSet {
+    data: HashMap<String, Vec<u8>>,
+    handles: HashMap<String, Handle<Font>>,
+    missing: Vec<String>,
+    families: HashMap<Family, Vec<String>>,
+    text: Option<String>,
+    dyslexia_friendly: Option<String>,
+}
+
+impl FontSet {
+    fn definitions(&self, dyslexia_font: bool) -> egui::FontDefinitions {
+        let mut definitions = egui::FontDefinitions::default();
+        for (name, data) in self.data.iter() {
+            let font = egui::FontData::from_owned(data.to_owned());
+            definitions.font_data.insert(name.to_owned(), font);
+        }
+        for (family, chain) in self.families.iter() {
+            let family = family.egui_family();
+            let builtin = definitions.families.get(&family).cloned().unwrap_or_default();
+            let fonts = chain
+                .iter()
+                .flat_map(|name| match name.as_str() {
+                    "egui" => builtin.clone(),
+                    _ if self.data.contains_key(name) => vec![name.to_owned()],
+                    // the file couldn't be read, the next font takes its place
+                    _ => vec![],
+                })
+                .collect();
+            definitions.families.insert(family, fonts);
+        }
+        let dyslexia_friendly = match &self.dyslexia_friendly {
+            Some(name) if dyslexia_font && self.data.contains_key(name) => Some(name),
+            _ => None,
+        };
+        if let Some(name) = dyslexia_friendly {
+            let family = definitions.families.entry(egui::FontFamily::Proportional).or_default();
+            family.insert(0, name.to_owned());
+        }
+        definitions
+    }
+}
+
+// reads every font file of the set, the same bytes go to egui and to bevy's text
+#[derive(Default)]
+struct FontSetLoader;
+
+impl AssetLoader for FontSetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let file: FontSetFile = ron::de::from_bytes(bytes)?;
+            let mut data = HashMap::new();
+            let mut handles = HashMap::new();
+            let mut missing = vec![];
+            for (name, path) in file.fonts.iter() {
+                // a missing font only leaves some characters undrawn, the game still runs
+                let bytes = match load_context.read_asset_bytes(path).await {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        warn!("could not read font {}: {}", path, err);
+                        missing.push(path.to_owned());
+                        continue;
+                    }
+                };
+                let font = Font::try_from_bytes(bytes.clone())?;
+                let handle = load_context.set_labeled_asset(name, LoadedAsset::new(font));
+                handles.insert(name.to_owned(), handle);
+                data.insert(name.to_owned(), bytes);
+            }
+            load_context.set_default_asset(LoadedAsset::new(FontSet {
+                data,
+                handles,
+                missing,
+                families: file.families,
+                text: file.text,
+                dyslexia_friendly: file.dyslexia_friendly,
+            }));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["fonts.ron"]
+    }
+}
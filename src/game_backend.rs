@@ -1,8 +1,9 @@
-use crate::{commands, npcs};
+use crate::{animation, app_state, app_state::AppState, commands, inventory, loading, npcs};
+use crate::{sound, status_effects, storage, typewriter};
 use bevy::prelude::*;
 use bevy_common_assets::ron::RonAssetPlugin;
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 pub struct GameBackendPlugin;
 
@@ -13,32 +14,48 @@ impl Plugin for GameBackendPlugin {
             .init_resource::<GameState>()
             .add_event::<CommandExecutionEvent>()
             .add_event::<CommandResultEvent>()
+            .add_event::<CommandInterruptEvent>()
+            .add_event::<ExportLogEvent>()
             .add_event::<NpcActionEvent>()
             .add_event::<NpcResponseEvent>()
+            .add_event::<SkipCgEvent>()
             .init_resource::<NpcFileHandle>()
             .init_resource::<Npcs>()
+            .init_resource::<Speakers>()
             .init_resource::<ActiveNpc>()
             .init_resource::<CgFileHandle>()
             .init_resource::<Cgs>()
             .add_startup_system(load_files)
+            .add_system(reload_files.before(prepare_npcs).before(prepare_cgs))
             .add_system(prepare_npcs)
             .add_system(prepare_cgs)
-            .add_system(game_loop);
+            .add_system_set(SystemSet::on_enter(AppState::Title).with_system(reset_game))
+            .add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(app_state::playing)
+                    .with_system(game_loop)
+                    .with_system(check_game_over),
+            );
     }
 }
 
 fn load_files(
     asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
     mut npc_handle: ResMut<NpcFileHandle>,
     mut cg_handle: ResMut<CgFileHandle>,
 ) {
-    npc_handle.0 = asset_server.load("npcfile.npcs.ron");
-    cg_handle.0 = asset_server.load("cgfile.cgs.ron");
+    npc_handle.0 = loading.load(&asset_server, "npcfile.npcs.ron");
+    cg_handle.0 = loading.load(&asset_server, "cgfile.cgs.ron");
 }
 
 fn prepare_npcs(
     asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    game_state: Res<GameState>,
     mut npc_list: ResMut<Npcs>,
+    mut speakers: ResMut<Speakers>,
     npc_handle: ResMut<NpcFileHandle>,
     npc_file: Res<Assets<NpcFile>>,
 ) {
@@ -47,12 +64,34 @@ fn prepare_npcs(
     }
     let Some(npc_file) = npc_file.get(&npc_handle.0) else { return; };
 
-    for (id, (x, y, frames)) in npc_file.npcs.iter() {
+    for (id, entry) in npc_file.npcs.iter() {
+        // npcs that are gone can still be heard in the dialogue
+        let portraits = entry
+            .expressions
+            .iter()
+            .map(|expression| {
+                let path = format!("chars/{}/{}.png", id, expression);
+                (expression.to_owned(), loading.load(&asset_server, &path))
+            })
+            .collect();
+        let speaker = Speaker {
+            voice: entry.voice.clone(),
+            name_color: entry.name_color,
+            portraits,
+        };
+        speakers.speakers.insert(id.to_owned(), speaker);
+        // also for npcs that are gone, cheats can bring them back
+        let atlas = entry.sheet.load(&asset_server, &mut loading, &mut atlases);
+        npc_list.atlases.insert(id.to_owned(), atlas);
+
+        if game_state.completed_npcs.contains(id) {
+            continue;
+        }
         let npc = Npc {
-            animation_frames: (0..*frames)
-                .map(|frame| asset_server.load(format!("chars/{}/{}-{}.png", id, id, frame)))
-                .collect(),
-            location: (*x, *y),
+            location: entry.location,
+            movement: entry.movement.clone(),
+            caption: entry.caption.clone(),
+            quest: entry.quest,
         };
         npc_list.npcs.insert(id.to_owned(), npc);
     }
@@ -61,6 +100,7 @@ fn prepare_npcs(
 
 fn prepare_cgs(
     asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
     mut cg_list: ResMut<Cgs>,
     cg_handle: ResMut<CgFileHandle>,
     cg_file: Res<Assets<CgFile>>,
@@ -73,7 +113,10 @@ fn prepare_cgs(
     for (id, frames) in cg_file.cgs.iter() {
         let cg = Cg {
             images: (0..*frames)
-                .map(|frame| asset_server.load(format!("cgs/{}-{}.png", id, frame)))
+                .map(|frame| {
+                    let path = format!("cgs/{}-{}.png", id, frame);
+                    loading.load(&asset_server, &path)
+                })
                 .collect(),
             index: 0,
         };
@@ -82,36 +125,161 @@ fn prepare_cgs(
     cg_list.loaded = true;
 }
 
+// reads the npc and cg files again after they have been edited
+fn reload_files(
+    mut npcs: ResMut<Npcs>,
+    mut cgs: ResMut<Cgs>,
+    npc_handle: Res<NpcFileHandle>,
+    cg_handle: Res<CgFileHandle>,
+    mut npc_events: EventReader<AssetEvent<NpcFile>>,
+    mut cg_events: EventReader<AssetEvent<CgFile>>,
+    mut reload_events: EventWriter<loading::ReloadEvent>,
+) {
+    for event in npc_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            if *handle == npc_handle.0 {
+                *npcs = Npcs::default();
+                reload_events.send(loading::ReloadEvent::Npcs);
+            }
+        }
+    }
+    for event in cg_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            if *handle == cg_handle.0 {
+                *cgs = Cgs::default();
+                reload_events.send(loading::ReloadEvent::Cgs);
+            }
+        }
+    }
+}
+
+// starts over from a fresh game state, the files are read again from the loaded assets
+fn reset_game(
+    mut game_state: ResMut<GameState>,
+    mut active_npc: ResMut<ActiveNpc>,
+    mut npcs: ResMut<Npcs>,
+    mut cgs: ResMut<Cgs>,
+) {
+    *game_state = GameState::default();
+    active_npc.0 = None;
+    *npcs = Npcs::default();
+    *cgs = Cgs::default();
+}
+
+fn check_game_over(game_state: Res<GameState>, mut app_state: ResMut<State<AppState>>) {
+    if game_state.player_hitpoints <= 0 && !game_state.god_mode {
+        let _ = app_state.set(AppState::GameOver);
+    }
+}
+
 fn game_loop(
+    time: Res<Time>,
+    mut app_state: ResMut<State<AppState>>,
     mut game_state: ResMut<GameState>,
     mut active_npc: ResMut<ActiveNpc>,
     mut npc_state: ResMut<Npcs>,
     mut execution_events: EventReader<CommandExecutionEvent>,
+    mut interrupt_events: EventReader<CommandInterruptEvent>,
     mut action_events: EventReader<NpcActionEvent>,
     mut result_events: EventWriter<CommandResultEvent>,
     mut response_events: EventWriter<NpcResponseEvent>,
+    mut save_events: EventWriter<storage::SaveGameEvent>,
+    mut export_events: EventWriter<ExportLogEvent>,
 ) {
-    // handle commands
+    // stop the foreground job on ctrl+c
+    for _ in interrupt_events.iter() {
+        if let Some(job) = game_state.jobs.interrupt() {
+            let message = format!("^C\n[{}] Interrupted  {}", job.id, job.command);
+            let output = commands::CommandOutput::new()
+                .with_severity(commands::output::Severity::Warning)
+                .lines(&message, commands::SpanStyle::Warning);
+            result_events.send(CommandResultEvent(output));
+        }
+    }
+
+    // handle commands, a trailing `&' runs the command in the background
     for execution in execution_events.iter() {
         let CommandExecutionEvent(command) = execution;
-        let message = match commands::execute_command(&mut game_state, command.as_str()) {
-            Ok(msg) => msg,
-            Err(msg) => format!("Error: {}", msg),
+        let (command, background) = match command.trim_end().strip_suffix('&') {
+            Some(command) => (command.trim(), true),
+            None => (command.trim(), false),
         };
-        result_events.send(CommandResultEvent(message));
+        // nothing is left of a lone `&'
+        if command.is_empty() {
+            continue;
+        }
+        if !background && game_state.jobs.foreground().is_some() {
+            result_events.send(CommandResultEvent(commands::CommandOutput::error(
+                "Error: Another command is still running, press Ctrl+C to stop it",
+            )));
+            continue;
+        }
+        // every command takes a turn in battle, even if the player is stunned
+        let output = if game_state.in_battle
+            && game_state
+                .player_effects
+                .has(status_effects::EffectKind::Stun)
+        {
+            Some(commands::CommandOutput::error("Error: You are stunned and can't type"))
+        } else {
+            match commands::execute_command(&mut game_state, command) {
+                Ok(commands::Execution::Output(output)) => Some(output),
+                Ok(commands::Execution::Job(job)) => {
+                    let id = game_state.jobs.spawn(command, background, job);
+                    background.then(|| {
+                        commands::CommandOutput::new().line(vec![commands::Span::styled(
+                            format!("[{}] {}", id, command),
+                            commands::SpanStyle::Muted,
+                        )])
+                    })
+                }
+                // the ui answers once the log is written
+                Ok(commands::Execution::ExportLog) => {
+                    export_events.send(ExportLogEvent);
+                    None
+                }
+                Err(msg) => Some(commands::CommandOutput::error(&format!("Error: {}", msg))),
+            }
+        };
+        if let Some(output) = output {
+            result_events.send(CommandResultEvent(output));
+        }
+        if game_state.in_battle {
+            status_effects::end_turn(&mut game_state);
+            game_state.battle_turns += 1;
+            game_state.last_command = command.split_whitespace().next().map(str::to_lowercase);
+        }
     }
 
+    // run the jobs, they may be updated in the same frame they are started
+    let mut jobs = std::mem::take(&mut game_state.jobs);
+    for output in jobs.update(&mut game_state, time.delta_seconds()) {
+        result_events.send(CommandResultEvent(output));
+    }
+    game_state.jobs = jobs;
+
     // handle npc encounter
     if active_npc.0.is_none() {
         for (id, npc) in npc_state.npcs.iter() {
+            // an escort walks with the player instead
+            if matches!(npc.movement, Movement::Escort) {
+                continue;
+            }
             if npc.location == (game_state.player_x, game_state.player_y) {
                 active_npc.0 = Some(npcs::get_npc_by_id(id).unwrap());
+                game_state.encountered_npc = Some(id.to_owned());
                 active_npc
                     .0
                     .as_mut()
                     .unwrap()
                     .handle_action(&npcs::PlayerAction::Ping, &mut game_state);
                 game_state.in_battle = true;
+                // npcs that can be fought are hostile
+                let state = match active_npc.0.as_ref().unwrap().hitpoints() {
+                    Some(_) => AppState::Battle,
+                    None => AppState::Dialogue,
+                };
+                let _ = app_state.set(state);
                 break;
             }
         }
@@ -133,8 +301,14 @@ fn game_loop(
 
         if current_npc.job_completed() {
             npc_state.npcs.remove(current_npc.id());
+            game_state.completed_npcs.push(current_npc.id().to_string());
             game_state.in_battle = false;
+            game_state.encountered_npc = None;
+            game_state.enemy_effects.clear();
+            game_state.last_command = None;
             active_npc.0 = None;
+            let _ = app_state.set(AppState::Exploring);
+            save_events.send(storage::SaveGameEvent);
         }
     }
 }
@@ -142,7 +316,6 @@ fn game_loop(
 #[derive(Resource)]
 pub struct GameState {
     pub game_progress: GameProgress,
-    pub is_showing_cg: bool,
     pub player_level: i32,
     pub player_hitpoints: i32,
     pub player_max_hp: i32,
@@ -152,6 +325,26 @@ pub struct GameState {
     pub player_y: usize,
     pub in_battle: bool,
     pub action_queue: Vec<npcs::PlayerAction>,
+    pub jobs: commands::jobs::JobTable,
+    pub inventory: inventory::Inventory,
+    pub dropped_items: Vec<String>,
+    pub encountered_npc: Option<String>,
+    pub player_effects: status_effects::StatusEffects,
+    // effects on the npc the player is encountering
+    pub enemy_effects: status_effects::StatusEffects,
+    // the number of commands run in battle, the enemy acts after each of them
+    pub battle_turns: u32,
+    // the name of the last command run in the current battle
+    pub last_command: Option<String>,
+    // the npcs the player has finished talking to or defeated
+    pub completed_npcs: Vec<String>,
+    // the tiles that have been seen, the rest of the map is hidden
+    pub explored: HashSet<(usize, usize)>,
+    // requests from cheat commands, only with the `debug` feature
+    pub cheats: Vec<commands::Cheat>,
+    pub god_mode: bool,
+    // sound effects to play, for commands and npcs that can't send events
+    pub sounds: Vec<sound::Sound>,
 }
 
 #[derive(Resource, Default)]
@@ -159,21 +352,76 @@ pub struct ActiveNpc(pub Option<Box<dyn npcs::Npc>>);
 
 #[derive(Resource, Default)]
 pub struct Npc {
-    pub animation_frames: Vec<Handle<Image>>,
+    // the tile the npc is standing on, updated as it walks
     pub location: (usize, usize),
+    pub movement: Movement,
+    // shown above the npc on the map
+    pub caption: Option<String>,
+    // marked on the map even where it hasn't been explored
+    pub quest: bool,
+}
+
+// how an npc walks around the map when it isn't in battle
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub enum Movement {
+    #[default]
+    Static,
+    // walks to each tile in order, then starts over
+    Patrol(Vec<(usize, usize)>),
+    // walks to random road tiles in the region between two corners
+    Wander((usize, usize), (usize, usize)),
+    // waits until the player comes close, then follows one tile behind them
+    Escort,
 }
 
 #[derive(Resource, Default)]
 pub struct Npcs {
     pub npcs: HashMap<String, Npc>,
-    pub frame_count: usize,
+    // the sprite sheet of every npc in the file, by npc id
+    pub atlases: HashMap<String, Handle<TextureAtlas>>,
     pub loaded: bool,
 }
 
-#[derive(Deserialize, bevy::reflect::TypeUuid)]
+// how an npc looks and sounds in the dialogue
+pub struct Speaker {
+    pub voice: typewriter::Voice,
+    pub name_color: (u8, u8, u8),
+    // loaded from `chars/{id}/{expression}.png`, the first one is the default expression
+    portraits: Vec<(String, Handle<Image>)>,
+}
+
+#[derive(Resource, Default)]
+pub struct Speakers {
+    pub speakers: HashMap<String, Speaker>,
+}
+
+#[derive(Clone, Serialize, Deserialize, bevy::reflect::TypeUuid)]
 #[uuid = "59c820f3-107f-4f40-b183-39f1b2cab9cd"]
-struct NpcFile {
-    npcs: HashMap<String, (usize, usize, usize)>,
+pub struct NpcFile {
+    // sorted so that saving the file keeps the order of the npcs
+    npcs: BTreeMap<String, NpcEntry>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct NpcEntry {
+    location: (usize, usize),
+    sheet: animation::SpriteSheet,
+    #[serde(default)]
+    movement: Movement,
+    #[serde(default)]
+    voice: typewriter::Voice,
+    #[serde(default = "default_name_color")]
+    name_color: (u8, u8, u8),
+    #[serde(default)]
+    expressions: Vec<String>,
+    #[serde(default)]
+    caption: Option<String>,
+    #[serde(default)]
+    quest: bool,
+}
+
+fn default_name_color() -> (u8, u8, u8) {
+    (226, 45, 42)
 }
 
 #[derive(Resource, Default)]
@@ -183,7 +431,7 @@ pub struct Cg {
 }
 
 #[derive(Resource, Default)]
-struct NpcFileHandle(Handle<NpcFile>);
+pub struct NpcFileHandle(pub Handle<NpcFile>);
 
 #[derive(Resource, Default)]
 pub struct Cgs {
@@ -202,7 +450,16 @@ struct CgFileHandle(Handle<CgFile>);
 
 pub struct CommandExecutionEvent(pub String);
 
-pub struct CommandResultEvent(pub String);
+pub struct CommandResultEvent(pub commands::CommandOutput);
+
+// ends the cutscene being shown
+pub struct SkipCgEvent;
+
+// sent when the player presses ctrl+c in the terminal
+pub struct CommandInterruptEvent;
+
+// sent by `log export`, the dialogue is written out by the ui
+pub struct ExportLogEvent;
 
 #[derive(Default)]
 pub struct NpcActionEvent(pub npcs::PlayerAction);
@@ -213,7 +470,6 @@ impl Default for GameState {
     fn default() -> Self {
         GameState {
             game_progress: GameProgress::Intro,
-            is_showing_cg: false,
             player_level: 0,
             player_hitpoints: 20,
             player_max_hp: 20,
@@ -223,21 +479,121 @@ impl Default for GameState {
             player_y: 0,
             in_battle: false,
             action_queue: vec![],
+            jobs: commands::jobs::JobTable::default(),
+            inventory: inventory::Inventory::default(),
+            dropped_items: vec![],
+            encountered_npc: None,
+            player_effects: status_effects::StatusEffects::default(),
+            enemy_effects: status_effects::StatusEffects::default(),
+            battle_turns: 0,
+            last_command: None,
+            completed_npcs: vec![],
+            explored: HashSet::new(),
+            cheats: vec![],
+            god_mode: false,
+            sounds: vec![],
         }
     }
 }
 
+impl Speaker {
+    // falls back to the default expression if there is no portrait for it
+    pub fn portrait(&self, expression: Option<&str>) -> Option<&Handle<Image>> {
+        self.portraits
+            .iter()
+            .find(|(name, _)| Some(name.as_str()) == expression)
+            .or_else(|| self.portraits.first())
+            .map(|(_, portrait)| portrait)
+    }
+}
+
+impl NpcFile {
+    // the file with the npcs moved to where they are now, npcs that are gone stay where they were
+    pub fn with_locations(&self, npcs: &Npcs) -> NpcFile {
+        let mut file = self.clone();
+        for (id, entry) in file.npcs.iter_mut() {
+            if let Some(npc) = npcs.npcs.get(id) {
+                entry.location = npc.location;
+            }
+        }
+        file
+    }
+}
+
 impl GameState {
+    pub fn effective_level(&self) -> i32 {
+        self.player_level
+            + self
+                .player_effects
+                .magnitude(status_effects::EffectKind::RootAccess)
+    }
+    pub fn effective_atk(&self) -> i32 {
+        self.player_atk + self.inventory.bonus().atk
+    }
+    pub fn effective_def(&self) -> i32 {
+        self.player_def
+            + self.inventory.bonus().def
+            + self.player_effects.magnitude(status_effects::EffectKind::Shield)
+    }
+    pub fn effective_max_hp(&self) -> i32 {
+        self.player_max_hp + self.inventory.bonus().max_hp
+    }
+    // losing max HP can't leave the player with more HP than the maximum
+    pub fn clamp_hitpoints(&mut self) {
+        self.player_hitpoints = self.player_hitpoints.min(self.effective_max_hp());
+    }
+    // deals damage to the player reduced by DEF, returns the damage taken
+    pub fn damage_player(&mut self, attack: i32) -> i32 {
+        let damage = (attack - self.effective_def()).max(1);
+        self.player_hitpoints = (self.player_hitpoints - damage).max(0);
+        damage
+    }
     pub fn player_details(&self) -> String {
+        // shows stats as "total (base + bonus)" when equipment or effects change them
+        let with_bonus = |base: i32, bonus: i32| {
+            if bonus == 0 {
+                base.to_string()
+            } else {
+                let sign = if bonus < 0 { '-' } else { '+' };
+                format!("{} ({} {} {})", base + bonus, base, sign, bonus.abs())
+            }
+        };
+        let bonus = self.inventory.bonus();
         let mut res = String::new();
-        res.push_str(format!("Your access level: {}\n", self.player_level).as_str());
-        res.push_str(format!("HP: {} / {}\n", self.player_hitpoints, self.player_max_hp).as_str());
-        res.push_str(format!("ATK: {}\n", self.player_atk).as_str());
-        res.push_str(format!("DEF: {}", self.player_def).as_str());
+        let root_access = self.effective_level() - self.player_level;
+        res.push_str(
+            format!(
+                "Your access level: {}\n",
+                with_bonus(self.player_level, root_access)
+            )
+            .as_str(),
+        );
+        res.push_str(
+            format!(
+                "HP: {} / {}\n",
+                self.player_hitpoints,
+                with_bonus(self.player_max_hp, bonus.max_hp)
+            )
+            .as_str(),
+        );
+        res.push_str(format!("ATK: {}\n", with_bonus(self.player_atk, bonus.atk)).as_str());
+        let def_bonus = self.effective_def() - self.player_def;
+        res.push_str(format!("DEF: {}", with_bonus(self.player_def, def_bonus)).as_str());
+        for slot in inventory::EquipSlot::ALL {
+            if let Some(id) = self.inventory.equipped(slot) {
+                res.push_str(
+                    format!("\n{}: {}", slot.name(), self.inventory.item_name(id)).as_str(),
+                );
+            }
+        }
+        if !self.player_effects.is_empty() {
+            res.push_str(format!("\nStatus: {}", self.player_effects.describe()).as_str());
+        }
         res
     }
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum GameProgress {
     Intro,
     Tutorial,
@@ -1,22 +1,34 @@
-use crate::{game_backend, game_map, game_ui};
+use crate::{
+    animation, app_state, app_state::AppState, fonts, game_backend, game_map, game_ui, loading,
+};
+use crate::{settings, sound};
 
 use bevy::prelude::*;
+use std::collections::VecDeque;
 
 pub struct GameFrontendPlugin;
 
 impl Plugin for GameFrontendPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PlayerState>()
-            .add_system(setup)
-            .add_system(handle_movement)
-            .add_system(show_cg)
-            .add_system(camera_follow)
+            .add_startup_system(spawn_camera)
+            .add_system(respawn_reloaded)
+            .add_system_set(SystemSet::on_enter(AppState::Title).with_system(reset_frontend))
+            .add_system_set(SystemSet::on_exit(AppState::Title).with_system(setup))
+            .add_system_set(SystemSet::on_enter(AppState::Cutscene).with_system(spawn_cgs))
+            .add_system_set(SystemSet::on_update(AppState::Cutscene).with_system(show_cg))
+            .add_system_set(
+                SystemSet::on_update(AppState::Exploring)
+                    .with_system(handle_movement)
+                    .with_system(move_npcs),
+            )
             .add_system_set(
                 SystemSet::new()
-                    .with_run_criteria(bevy::time::FixedTimestep::step(
-                        NPC_ANIMATION_INTERVAL as f64,
-                    ))
-                    .with_system(update_npc_frames),
+                    .with_run_criteria(app_state::playing)
+                    .with_system(animate_battle)
+                    .with_system(follow_teleport)
+                    .with_system(camera_follow)
+                    .with_system(despawn_npcs),
             );
     }
 }
@@ -24,8 +36,10 @@ impl Plugin for GameFrontendPlugin {
 fn show_cg(
     mut commands: Commands,
     keyboard_input: Res<Input<KeyCode>>,
+    mut skip_events: EventReader<game_backend::SkipCgEvent>,
     mut cgs: ResMut<game_backend::Cgs>,
     mut game_state: ResMut<game_backend::GameState>,
+    mut app_state: ResMut<State<AppState>>,
     mut cg_query: Query<(&mut Handle<Image>, &CgComponent, Entity), With<CgComponent>>,
     mut camera_query: Query<&mut Transform, With<Camera>>,
 ) {
@@ -40,9 +54,8 @@ fn show_cg(
     camera.translation.x = 0.0;
     camera.translation.y = 0.0;
 
-    game_state.is_showing_cg = true;
-
-    if !keyboard_input.any_just_pressed([KeyCode::Return, KeyCode::Space]) {
+    let skip = skip_events.iter().count() > 0;
+    if !skip && !keyboard_input.any_just_pressed(game_ui::ADVANCE_KEYS) {
         return;
     }
 
@@ -50,7 +63,7 @@ fn show_cg(
     for (mut texture, CgComponent(id), entity) in cg_query.iter_mut() {
         if id == cg_id {
             let cg = cgs.cgs.get_mut(id).unwrap();
-            cg.index += 1;
+            cg.index = if skip { cg.images.len() } else { cg.index + 1 };
             if cg.index >= cg.images.len() {
                 commands.entity(entity).despawn();
                 completed = true;
@@ -61,103 +74,117 @@ fn show_cg(
     }
 
     if completed {
-        game_state.is_showing_cg = false;
         game_state.game_progress = match game_state.game_progress {
             game_backend::GameProgress::Intro => game_backend::GameProgress::Tutorial,
             _ => return,
         };
+        let _ = app_state.set(AppState::Exploring);
     }
 }
 
-fn update_npc_frames(
+// removes the sprites of npcs that are gone
+fn despawn_npcs(
     mut commands: Commands,
-    mut npcs: ResMut<game_backend::Npcs>,
-    mut npc_query: Query<(&mut Handle<Image>, &NpcComponent, Entity), With<NpcComponent>>,
+    npcs: Res<game_backend::Npcs>,
+    npc_query: Query<(&NpcComponent, Entity)>,
 ) {
-    npcs.frame_count += 1;
-    for (mut texture, NpcComponent(id), entity) in npc_query.iter_mut() {
-        match npcs.npcs.get(id) {
-            Some(npc) => {
-                let frames = &npc.animation_frames;
-                *texture = frames[npcs.frame_count % frames.len()].to_owned();
-            }
-            None => {
-                commands.entity(entity).despawn();
-            }
+    for (NpcComponent(id), entity) in npc_query.iter() {
+        if !npcs.npcs.contains_key(id) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// the npc in battle talks, flinches when it's hit and falls when it's defeated
+fn animate_battle(
+    game_state: Res<game_backend::GameState>,
+    active_npc: Res<game_backend::ActiveNpc>,
+    mut last_hitpoints: Local<Option<i32>>,
+    mut npc_query: Query<(&NpcComponent, &mut animation::Animator)>,
+) {
+    let Some(npc) = active_npc.0.as_ref().filter(|_| game_state.in_battle) else {
+        *last_hitpoints = None;
+        return;
+    };
+    let hitpoints = npc.hitpoints().map(|(hitpoints, _)| hitpoints);
+    for (NpcComponent(id), mut animator) in npc_query.iter_mut() {
+        if id != npc.id() {
+            continue;
+        }
+        match (hitpoints, *last_hitpoints) {
+            (Some(hitpoints), _) if hitpoints <= 0 => animator.play("defeated"),
+            (Some(hitpoints), Some(last)) if hitpoints < last => animator.play_once("hurt"),
+            _ => animator.play("talk"),
         }
     }
+    *last_hitpoints = hitpoints;
 }
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
+// removes everything spawned for the previous game when going back to the title
+fn reset_frontend(
+    mut commands: Commands,
+    mut player_state: ResMut<PlayerState>,
+    query: Query<Entity, Or<(With<Protagonist>, With<NpcComponent>, With<CgComponent>)>>,
+    mut camera_query: Query<&mut Transform, With<Camera>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *player_state = PlayerState::default();
+    for mut camera in camera_query.iter_mut() {
+        camera.translation.x = 0.0;
+        camera.translation.y = 0.0;
+    }
+}
+
 fn setup(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    windows: Res<Windows>,
     map: Res<game_map::Map>,
-    tileset: Res<game_map::MapTileset>,
+    animations: Res<animation::Animations>,
     npcs: Res<game_backend::Npcs>,
-    cgs: Res<game_backend::Cgs>,
     mut game_state: ResMut<game_backend::GameState>,
     mut player_state: ResMut<PlayerState>,
 ) {
-    if !map.loaded || !npcs.loaded || player_state.loaded {
-        return;
+    // a new game starts at the start of the map, a continued one where it was saved
+    if let game_backend::GameProgress::Intro = game_state.game_progress {
+        game_state.player_x = map.start_pos.0;
+        game_state.player_y = map.start_pos.1;
     }
-    player_state.x_pos = (map.start_pos.0 as f32 + 0.5) * TILE_WIDTH;
-    player_state.y_pos = (map.start_pos.1 as f32 + 0.5) * TILE_HEIGHT;
-    for direction in 0..4 {
-        for frame in 0..PLAYER_ANIMATION_FRAMES {
-            player_state.textures[direction][frame] =
-                asset_server.load(format!("chars/mc/mc{}-{}.png", direction, frame));
-        }
-    }
-    game_state.player_x = map.start_pos.0;
-    game_state.player_y = map.start_pos.1;
+    player_state.x_pos = (game_state.player_x as f32 + 0.5) * TILE_WIDTH;
+    player_state.y_pos = (game_state.player_y as f32 + 0.5) * TILE_HEIGHT;
 
-    commands.spawn(Camera2dBundle::default());
     commands.spawn((
-        SpriteBundle {
+        SpriteSheetBundle {
             transform: Transform {
                 translation: Vec3::new(player_state.x_pos, player_state.y_pos, PLAYER_Z),
                 scale: Vec3::new(PLAYER_SCALE, PLAYER_SCALE, 1.0),
                 ..default()
             },
-            sprite: Sprite {
+            sprite: TextureAtlasSprite {
                 anchor: bevy::sprite::Anchor::Custom(Vec2::new(PLAYER_CENTER_X, PLAYER_CENTER_Y)),
                 ..default()
             },
-            texture: player_state.textures[0][0].to_owned(),
+            texture_atlas: animations.atlas("mc"),
             ..default()
         },
+        animation::Animator::new("mc"),
         Protagonist,
     ));
     player_state.loaded = true;
 
-    for x in 0..map.width {
-        for y in 0..map.height {
-            let handle = tileset.0.get(&map.tiles[x][y].tile_type).unwrap();
-            commands.spawn((SpriteBundle {
-                transform: Transform {
-                    translation: Vec3::new(
-                        x as f32 * game_map::Tile::WIDTH,
-                        y as f32 * game_map::Tile::HEIGHT,
-                        game_map::Tile::Z_LAYER,
-                    ),
-                    ..default()
-                },
-                sprite: Sprite {
-                    anchor: bevy::sprite::Anchor::Custom(Vec2::new(-0.5, -0.5)),
-                    ..default()
-                },
-                texture: handle.to_owned(),
-                ..default()
-            },));
-        }
-    }
+    spawn_npcs(&mut commands, &npcs);
+}
 
+fn spawn_npcs(commands: &mut Commands, npcs: &game_backend::Npcs) {
     for (id, npc) in npcs.npcs.iter() {
-        let handle = &npc.animation_frames[0];
         let (x, y) = npc.location;
-        commands.spawn((
-            SpriteBundle {
+        let seed = id.bytes().fold(1u32, |hash, b| hash.wrapping_mul(31) ^ b as u32);
+        let mut entity = commands.spawn((
+            SpriteSheetBundle {
                 transform: Transform {
                     translation: Vec3::new(
                         (x as f32 + 0.5) * game_map::Tile::WIDTH,
@@ -167,17 +194,44 @@ fn setup(
                     scale: Vec3::new(NPC_SCALE, NPC_SCALE, 1.0),
                     ..default()
                 },
-                sprite: Sprite {
+                sprite: TextureAtlasSprite {
                     anchor: bevy::sprite::Anchor::Custom(Vec2::new(NPC_CENTER_X, NPC_CENTER_Y)),
                     ..default()
                 },
-                texture: handle.to_owned(),
+                texture_atlas: npcs.atlases.get(id).cloned().unwrap_or_default(),
                 ..default()
             },
+            animation::Animator::new(id),
             NpcComponent(id.to_owned()),
+            NpcMotion {
+                path: VecDeque::new(),
+                waypoint: 0,
+                wait: 0.0,
+                seed: seed.max(1),
+                following: false,
+            },
         ));
+        if let Some(caption) = &npc.caption {
+            // undo the scale of the sprite so that captions have the same size
+            let transform = Transform::from_xyz(0.0, NPC_CAPTION_Y, 1.0)
+                .with_scale(Vec3::new(1.0 / NPC_SCALE, 1.0 / NPC_SCALE, 1.0));
+            entity.with_children(|parent| {
+                parent.spawn(fonts::Caption::bundle(
+                    caption,
+                    NPC_CAPTION_SIZE,
+                    Color::WHITE,
+                    transform,
+                ));
+            });
+        }
     }
+}
+
+fn spawn_cgs(mut commands: Commands, windows: Res<Windows>, cgs: Res<game_backend::Cgs>) {
+    spawn_cg_sprites(&mut commands, &windows, &cgs);
+}
 
+fn spawn_cg_sprites(commands: &mut Commands, windows: &Windows, cgs: &game_backend::Cgs) {
     for (id, cg) in cgs.cgs.iter() {
         let handle = &cg.images[0];
         let scale_x = windows.get_primary().unwrap().width() / CG_WIDTH;
@@ -198,12 +252,202 @@ fn setup(
     }
 }
 
-fn camera_follow(
-    player_state: ResMut<PlayerState>,
+// replaces the sprites of npcs and cgs once their edited files have been read again,
+// the player stays where they are
+fn respawn_reloaded(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    app_state: Res<State<AppState>>,
+    npcs: Res<game_backend::Npcs>,
+    cgs: Res<game_backend::Cgs>,
+    player_state: Res<PlayerState>,
+    mut pending: Local<PendingReload>,
+    mut reload_events: EventReader<loading::ReloadEvent>,
+    npc_query: Query<Entity, With<NpcComponent>>,
+    cg_query: Query<Entity, With<CgComponent>>,
+) {
+    for event in reload_events.iter() {
+        match event {
+            loading::ReloadEvent::Npcs => {
+                for entity in npc_query.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                pending.npcs = true;
+            }
+            loading::ReloadEvent::Cgs => {
+                for entity in cg_query.iter() {
+                    commands.entity(entity).despawn();
+                }
+                pending.cgs = true;
+            }
+            loading::ReloadEvent::Map => {}
+        }
+    }
+
+    // before the game starts, the sprites are spawned from the new files anyway
+    if pending.npcs && npcs.loaded {
+        if player_state.loaded {
+            spawn_npcs(&mut commands, &npcs);
+        }
+        pending.npcs = false;
+    }
+    if pending.cgs && cgs.loaded {
+        if *app_state.current() == AppState::Cutscene {
+            spawn_cg_sprites(&mut commands, &windows, &cgs);
+        }
+        pending.cgs = false;
+    }
+}
+
+// walks npcs along their patrol paths or around their regions, escorts walk behind the player
+fn move_npcs(
+    time: Res<Time>,
+    map: Res<game_map::Map>,
+    game_state: Res<game_backend::GameState>,
+    mut npcs: ResMut<game_backend::Npcs>,
+    mut npc_query: Query<(
+        &mut Transform,
+        &mut TextureAtlasSprite,
+        &mut animation::Animator,
+        &NpcComponent,
+        &mut NpcMotion,
+    )>,
+) {
+    if !map.loaded {
+        return;
+    }
+    let player = (game_state.player_x, game_state.player_y);
+    for (mut transform, mut sprite, mut animator, NpcComponent(id), mut motion) in
+        npc_query.iter_mut()
+    {
+        let Some(npc) = npcs.npcs.get_mut(id) else { continue; };
+        let center = tile_center(npc.location);
+        let is_resting = transform.translation.truncate().distance(center) < 1.0;
+        let is_near_player =
+            npc.location.0.abs_diff(player.0) + npc.location.1.abs_diff(player.1) <= 1;
+        // an escort joins the player the first time they meet
+        if is_near_player && matches!(npc.movement, game_backend::Movement::Escort) {
+            motion.following = true;
+        }
+
+        // stop and look at the player when they come close
+        if is_resting && is_near_player {
+            let player_x = tile_center(player).x;
+            if player_x != center.x {
+                sprite.flip_x = player_x < center.x;
+            }
+            if !motion.following {
+                motion.path.clear();
+                animator.play("idle");
+                continue;
+            }
+        }
+
+        if is_resting && motion.path.is_empty() {
+            if motion.following {
+                // keep one tile behind the player
+                if !is_near_player {
+                    if let Some(mut path) = map.find_path(npc.location, player) {
+                        path.pop();
+                        motion.path = path.into();
+                    }
+                }
+            } else {
+                motion.wait -= time.delta_seconds();
+                if motion.wait <= 0.0 {
+                    plan_route(&map, npc, &mut motion);
+                }
+            }
+        }
+
+        let Some(&next) = motion.path.front() else {
+            animator.play("idle");
+            continue;
+        };
+        let target = tile_center(next);
+        let position = transform.translation.truncate();
+        let direction = target - position;
+        let direction = if direction.x.abs() > direction.y.abs() {
+            if direction.x > 0.0 { 1 } else { 3 }
+        } else if direction.y > 0.0 {
+            2
+        } else {
+            0
+        };
+        animator.play(&format!("walk_{}", DIRECTIONS[direction]));
+        let step = NPC_VELOCITY * time.delta_seconds();
+        if target.x != position.x {
+            sprite.flip_x = target.x < position.x;
+        }
+        let position = if position.distance(target) <= step {
+            motion.path.pop_front();
+            npc.location = next;
+            target
+        } else {
+            position + (target - position).normalize() * step
+        };
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+// picks where the npc walks next
+fn plan_route(map: &game_map::Map, npc: &game_backend::Npc, motion: &mut NpcMotion) {
+    match &npc.movement {
+        // escorts are walked by `move_npcs`
+        game_backend::Movement::Static | game_backend::Movement::Escort => {}
+        game_backend::Movement::Patrol(waypoints) => {
+            if waypoints.is_empty() {
+                return;
+            }
+            let path = map.find_path(npc.location, waypoints[motion.waypoint]);
+            motion.path = path.unwrap_or_default().into();
+            motion.waypoint = (motion.waypoint + 1) % waypoints.len();
+        }
+        game_backend::Movement::Wander(corner_a, corner_b) => {
+            let (min_x, max_x) = (corner_a.0.min(corner_b.0), corner_a.0.max(corner_b.0));
+            let (min_y, max_y) = (corner_a.1.min(corner_b.1), corner_a.1.max(corner_b.1));
+            let x = min_x + motion.random() as usize % (max_x - min_x + 1);
+            let y = min_y + motion.random() as usize % (max_y - min_y + 1);
+            let path = map.find_path(npc.location, (x, y));
+            motion.path = path.unwrap_or_default().into();
+            motion.wait = NPC_WANDER_WAIT * (1.0 + (motion.random() % 100) as f32 / 100.0);
+        }
+    }
+}
+
+fn tile_center((x, y): (usize, usize)) -> Vec2 {
+    Vec2::new((x as f32 + 0.5) * TILE_WIDTH, (y as f32 + 0.5) * TILE_HEIGHT)
+}
+
+// moves the player when the backend puts them on another tile, e.g. by teleporting
+fn follow_teleport(
     game_state: Res<game_backend::GameState>,
+    mut player_state: ResMut<PlayerState>,
+    mut player_query: Query<&mut Transform, With<Protagonist>>,
+) {
+    let tile = (
+        (player_state.x_pos / TILE_WIDTH).floor() as usize,
+        (player_state.y_pos / TILE_HEIGHT).floor() as usize,
+    );
+    let target = (game_state.player_x, game_state.player_y);
+    if !player_state.loaded || tile == target {
+        return;
+    }
+    let center = tile_center(target);
+    player_state.x_pos = center.x;
+    player_state.y_pos = center.y;
+    for mut transform in player_query.iter_mut() {
+        transform.translation.x = center.x - PLAYER_SCALE * PLAYER_CENTER_X;
+        transform.translation.y = center.y - PLAYER_SCALE * PLAYER_CENTER_Y;
+    }
+}
+
+fn camera_follow(
+    player_state: Res<PlayerState>,
     mut camera_query: Query<&mut Transform, With<Camera>>,
 ) {
-    if !player_state.loaded || camera_query.is_empty() || game_state.is_showing_cg {
+    if !player_state.loaded || camera_query.is_empty() {
         return;
     }
 
@@ -217,42 +461,48 @@ fn handle_movement(
     keyboard_input: Res<Input<KeyCode>>,
     map: Res<game_map::Map>,
     ui_state: Res<game_ui::UiState>,
+    settings: Res<settings::Settings>,
     mut game_state: ResMut<game_backend::GameState>,
     mut player_state: ResMut<PlayerState>,
-    mut player_query: Query<(&mut Transform, &mut Handle<Image>), With<Protagonist>>,
+    mut footstep_timer: Local<f32>,
+    mut sound_events: EventWriter<sound::SoundEvent>,
+    mut player_query: Query<(&mut Transform, &mut animation::Animator), With<Protagonist>>,
 ) {
-    if !player_state.loaded
+    if !map.loaded
+        || !player_state.loaded
         || player_query.is_empty()
         || ui_state.is_textbox_focused
-        || game_state.in_battle
-        || game_state.is_showing_cg
     {
         return;
     }
 
+    let step = time.delta_seconds() * PLAYER_VELOCITY * settings.movement_speed;
     let mut delta_x = 0.0;
     let mut delta_y = 0.0;
     if keyboard_input.pressed(KeyCode::A) || keyboard_input.pressed(KeyCode::Left) {
-        delta_x -= time.delta_seconds() * PLAYER_VELOCITY;
+        delta_x -= step;
         player_state.direction = 3;
     }
     if keyboard_input.pressed(KeyCode::D) || keyboard_input.pressed(KeyCode::Right) {
-        delta_x += time.delta_seconds() * PLAYER_VELOCITY;
+        delta_x += step;
         player_state.direction = 1;
     }
     if keyboard_input.pressed(KeyCode::S) || keyboard_input.pressed(KeyCode::Down) {
-        delta_y -= time.delta_seconds() * PLAYER_VELOCITY;
+        delta_y -= step;
         player_state.direction = 0;
     }
     if keyboard_input.pressed(KeyCode::W) || keyboard_input.pressed(KeyCode::Up) {
-        delta_y += time.delta_seconds() * PLAYER_VELOCITY;
+        delta_y += step;
         player_state.direction = 2;
     }
 
     let mut player = player_query.single_mut();
 
     if delta_x == 0.0 && delta_y == 0.0 {
-        *player.1 = player_state.textures[player_state.direction][0].to_owned();
+        *footstep_timer = 0.0;
+        player
+            .1
+            .play(&format!("idle_{}", DIRECTIONS[player_state.direction]));
         return;
     }
 
@@ -261,7 +511,11 @@ fn handle_movement(
         let tile_y = (y / TILE_HEIGHT).floor() as usize;
         let offset_x = x - tile_x as f32 * TILE_WIDTH;
         let offset_y = y - tile_y as f32 * TILE_HEIGHT;
-        map.tiles[tile_x][tile_y].is_valid(offset_x, offset_y)
+        // the map may have shrunk under the player after it was edited
+        match map.tiles.get(tile_x).and_then(|column| column.get(tile_y)) {
+            Some(tile) => tile.is_valid(offset_x, offset_y),
+            None => false,
+        }
     };
 
     let new_x = if is_valid(player_state.x_pos + delta_x, player_state.y_pos) {
@@ -281,11 +535,15 @@ fn handle_movement(
     player_state.y_pos = new_y;
     game_state.player_x = (new_x / TILE_WIDTH).floor() as usize;
     game_state.player_y = (new_y / TILE_HEIGHT).floor() as usize;
-
-    let animation_frame = (time.elapsed_seconds() / PLAYER_ANIMATION_INTERVAL).floor() as usize;
-    *player.1 = player_state.textures[player_state.direction]
-        [animation_frame % PLAYER_ANIMATION_FRAMES]
-        .to_owned();
+    // the steps keep up with the player
+    *footstep_timer -= time.delta_seconds() * settings.movement_speed;
+    if *footstep_timer <= 0.0 {
+        *footstep_timer = FOOTSTEP_INTERVAL;
+        sound_events.send(sound::SoundEvent(sound::Sound::Footstep));
+    }
+    player
+        .1
+        .play(&format!("walk_{}", DIRECTIONS[player_state.direction]));
 }
 
 #[derive(Component)]
@@ -297,13 +555,40 @@ struct NpcComponent(String);
 #[derive(Component)]
 struct CgComponent(String);
 
+#[derive(Default)]
+struct PendingReload {
+    npcs: bool,
+    cgs: bool,
+}
+
+// where an npc is walking to
+#[derive(Component)]
+struct NpcMotion {
+    path: VecDeque<(usize, usize)>,
+    waypoint: usize,
+    // seconds to wait before wandering again
+    wait: f32,
+    seed: u32,
+    // the escort has met the player and walks behind them
+    following: bool,
+}
+
+impl NpcMotion {
+    // xorshift, good enough for picking where to wander
+    fn random(&mut self) -> u32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed
+    }
+}
+
 #[derive(Resource, Default)]
 struct PlayerState {
     loaded: bool,
     x_pos: f32,
     y_pos: f32,
     direction: usize,
-    textures: [[Handle<Image>; PLAYER_ANIMATION_FRAMES]; 4],
 }
 
 mod constants {
@@ -314,10 +599,13 @@ mod constants {
     pub const PLAYER_CENTER_X: f32 = -10.0 / PLAYER_WIDTH;
     pub const PLAYER_CENTER_Y: f32 = -190.0 / PLAYER_HEIGHT;
     pub const PLAYER_Z: f32 = 10.0;
+    // seconds between two footstep sounds
+    pub const FOOTSTEP_INTERVAL: f32 = 0.3;
     pub const PLAYER_SCALE: f32 = 0.3;
     pub const PLAYER_VELOCITY: f32 = 400.0;
-    pub const PLAYER_ANIMATION_INTERVAL: f32 = 0.2;
-    pub const PLAYER_ANIMATION_FRAMES: usize = 4;
+
+    // the names of the directions in animation clips, indexed by `PlayerState::direction`
+    pub const DIRECTIONS: [&str; 4] = ["down", "right", "up", "left"];
 
     pub const TILE_WIDTH: f32 = game_map::Tile::WIDTH;
     pub const TILE_HEIGHT: f32 = game_map::Tile::HEIGHT;
@@ -326,7 +614,11 @@ mod constants {
     pub const NPC_CENTER_Y: f32 = -0.4;
     pub const NPC_SCALE: f32 = 0.4;
     pub const NPC_Z: f32 = 15.0;
-    pub const NPC_ANIMATION_INTERVAL: f32 = 0.3;
+    // above the head, in pixels of the sprite
+    pub const NPC_CAPTION_Y: f32 = 460.0;
+    pub const NPC_CAPTION_SIZE: f32 = 24.0;
+    pub const NPC_VELOCITY: f32 = 150.0;
+    pub const NPC_WANDER_WAIT: f32 = 2.0;
 
     pub const CG_WIDTH: f32 = 1280.0;
     pub const CG_HEIGHT: f32 = 720.0;
@@ -1,7 +1,9 @@
+use crate::{animation, loading};
 use bevy::prelude::*;
 use bevy_common_assets::ron::RonAssetPlugin;
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 
 pub struct MapPlugin;
 
@@ -12,23 +14,24 @@ impl Plugin for MapPlugin {
             .init_resource::<MapFileHandle>()
             .init_resource::<Map>()
             .add_startup_system(load_files)
+            .add_system(reload_map.before(prepare_map))
             .add_system(prepare_map);
     }
 }
 
 fn load_files(
     asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
     mut map_handle: ResMut<MapFileHandle>,
-    mut tileset: ResMut<MapTileset>,
 ) {
-    map_handle.0 = asset_server.load("mapfile.map.ron");
-    for tile_type in 0..16 {
-        let filename = format!("bg/tile{}.png", tile_type);
-        tileset.0.insert(tile_type, asset_server.load(filename));
-    }
+    map_handle.0 = loading.load(&asset_server, "mapfile.map.ron");
 }
 
 fn prepare_map(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    mut tileset: ResMut<MapTileset>,
     mut map: ResMut<Map>,
     map_handle: ResMut<MapFileHandle>,
     map_file: Res<Assets<MapFile>>,
@@ -48,26 +51,36 @@ fn prepare_map(
     map.width = map_file.width;
     map.height = map_file.height;
     map.start_pos = map_file.start_pos;
-    for x in 0..map.width {
-        let mut row = vec![];
-        for y in 0..map.height {
-            let tile = if map_rows[y][x] != '.' {
-                Tile::empty()
-            } else {
-                let right_neighbor = x + 1 < map.width && map_rows[y][x + 1] == '.';
-                let up_neighbor = y + 1 < map.height && map_rows[y + 1][x] == '.';
-                let left_neighbor = x > 0 && map_rows[y][x - 1] == '.';
-                let down_neighbor = y > 0 && map_rows[y - 1][x] == '.';
-                Tile::from_neighbors(right_neighbor, up_neighbor, left_neighbor, down_neighbor)
-            };
-            row.push(tile);
-        }
-        map.tiles.push(row);
-    }
+    map.pickups = map_file.pickups.clone();
+    // the index of a tile in the tileset is its tile type
+    tileset.0 = map_file.tileset.load(&asset_server, &mut loading, &mut atlases);
+    map.roads = (0..map.width)
+        .map(|x| (0..map.height).map(|y| map_rows[y][x] == '.').collect())
+        .collect();
+    map.tiles = (0..map.width)
+        .map(|x| (0..map.height).map(|y| map.tile_at(x, y)).collect())
+        .collect();
 
     map.loaded = true;
 }
 
+// reads the map file again after it has been edited
+fn reload_map(
+    mut map: ResMut<Map>,
+    map_handle: Res<MapFileHandle>,
+    mut asset_events: EventReader<AssetEvent<MapFile>>,
+    mut reload_events: EventWriter<loading::ReloadEvent>,
+) {
+    for event in asset_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            if *handle == map_handle.0 {
+                *map = Map::default();
+                reload_events.send(loading::ReloadEvent::Map);
+            }
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct Tile {
     pub tile_type: usize,
@@ -80,23 +93,29 @@ pub struct Map {
     pub height: usize,
     pub start_pos: (usize, usize),
     pub tiles: Vec<Vec<Tile>>,
+    // whether each tile is a road, the tiles are picked from these
+    roads: Vec<Vec<bool>>,
+    pub pickups: Vec<(String, (usize, usize))>,
     pub loaded: bool,
 }
 
-#[derive(Deserialize, bevy::reflect::TypeUuid)]
+#[derive(Clone, Serialize, Deserialize, bevy::reflect::TypeUuid)]
 #[uuid = "0ec4d9f0-7c50-4630-9548-d2cf45eaf106"]
-struct MapFile {
+pub struct MapFile {
     width: usize,
     height: usize,
     start_pos: (usize, usize),
     tiles: Vec<String>,
+    tileset: animation::SpriteSheet,
+    #[serde(default)]
+    pickups: Vec<(String, (usize, usize))>,
 }
 
 #[derive(Resource, Default)]
-struct MapFileHandle(Handle<MapFile>);
+pub struct MapFileHandle(pub Handle<MapFile>);
 
 #[derive(Resource, Default)]
-pub struct MapTileset(pub HashMap<usize, Handle<Image>>);
+pub struct MapTileset(pub Handle<TextureAtlas>);
 
 impl Tile {
     pub const WIDTH: f32 = 320.0;
@@ -138,3 +157,113 @@ impl Tile {
             || self.neighbors.3 && is_down && is_mid_h
     }
 }
+
+impl Map {
+    pub fn is_road(&self, (x, y): (usize, usize)) -> bool {
+        self.roads.get(x).and_then(|column| column.get(y)) == Some(&true)
+    }
+    // turns a tile into a road or a wall and picks the tiles around it again
+    pub fn set_road(&mut self, (x, y): (usize, usize), is_road: bool) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        self.roads[x][y] = is_road;
+        let (left, down) = (x.wrapping_sub(1), y.wrapping_sub(1));
+        for (x, y) in [(x, y), (x + 1, y), (x, y + 1), (left, y), (x, down)] {
+            if x < self.width && y < self.height {
+                self.tiles[x][y] = self.tile_at(x, y);
+            }
+        }
+    }
+    // the map as it would be written to the map file, keeping the tileset of `file`
+    pub fn to_file(&self, file: &MapFile) -> MapFile {
+        MapFile {
+            width: self.width,
+            height: self.height,
+            start_pos: self.start_pos,
+            tiles: (0..self.height)
+                .rev()
+                .map(|y| {
+                    (0..self.width)
+                        .map(|x| if self.roads[x][y] { '.' } else { '#' })
+                        .collect()
+                })
+                .collect(),
+            tileset: file.tileset.clone(),
+            pickups: self.pickups.clone(),
+        }
+    }
+    fn tile_at(&self, x: usize, y: usize) -> Tile {
+        if !self.roads[x][y] {
+            return Tile::empty();
+        }
+        let right_neighbor = self.is_road((x + 1, y));
+        let up_neighbor = self.is_road((x, y + 1));
+        let left_neighbor = x > 0 && self.is_road((x - 1, y));
+        let down_neighbor = y > 0 && self.is_road((x, y - 1));
+        Tile::from_neighbors(right_neighbor, up_neighbor, left_neighbor, down_neighbor)
+    }
+    // the road tiles that can be walked to from the given tile
+    pub fn exits(&self, location: (usize, usize)) -> Vec<(usize, usize)> {
+        let (x, y) = location;
+        let Some(tile) = self.tiles.get(x).and_then(|column| column.get(y)) else {
+            return vec![];
+        };
+        let (left, right, up, down) = tile.neighbors;
+        let mut exits = vec![];
+        if left {
+            exits.push((x - 1, y));
+        }
+        if right {
+            exits.push((x + 1, y));
+        }
+        if up {
+            exits.push((x, y + 1));
+        }
+        if down {
+            exits.push((x, y - 1));
+        }
+        exits
+    }
+    // finds the shortest path along the roads with A*
+    // the path includes `to` but not `from`, returns None if `to` can't be reached
+    pub fn find_path(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        if from == to {
+            return Some(vec![]);
+        }
+        let distance = |(x, y): (usize, usize)| x.abs_diff(to.0) + y.abs_diff(to.1);
+        let mut open = BinaryHeap::from([Reverse((distance(from), 0, from))]);
+        let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        let mut costs = HashMap::from([(from, 0)]);
+
+        while let Some(Reverse((_, cost, current))) = open.pop() {
+            if current == to {
+                let mut path = vec![current];
+                while let Some(previous) = came_from.get(path.last().unwrap()) {
+                    if *previous == from {
+                        break;
+                    }
+                    path.push(*previous);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if cost > costs[&current] {
+                continue;
+            }
+            for next in self.exits(current) {
+                let next_cost = cost + 1;
+                if next_cost < *costs.get(&next).unwrap_or(&usize::MAX) {
+                    costs.insert(next, next_cost);
+                    came_from.insert(next, current);
+                    open.push(Reverse((next_cost + distance(next), next_cost, next)));
+                }
+            }
+        }
+        None
+    }
+}
@@ -1,8 +1,13 @@
-use crate::{commands, game_backend, npcs};
+use crate::{
+    accessibility, app_state::AppState, commands, game_backend, locale, npcs, settings, sound,
+    storage,
+};
+use crate::typewriter;
 
 use bevy::prelude::*;
 use bevy_egui::egui;
 use bevy_egui::EguiContext;
+use std::collections::HashMap;
 
 pub struct GameUiPlugin;
 
@@ -11,17 +16,23 @@ impl Plugin for GameUiPlugin {
         app.init_resource::<UiState>()
             .add_startup_system(prepare_ui)
             .add_system(game_ui)
-            .add_system(update_ui_events);
+            .add_system(dialogue_keys.before(game_ui))
+            .add_system(interrupt_command)
+            .add_system(update_ui_events)
+            .add_system(type_dialogue.after(update_ui_events))
+            .add_system(export_log)
+            .add_system_set(SystemSet::on_enter(AppState::Title).with_system(reset_ui));
     }
 }
 
-fn prepare_ui(mut egui_context: ResMut<EguiContext>, mut windows: ResMut<Windows>) {
+fn reset_ui(mut ui_state: ResMut<UiState>) {
+    *ui_state = UiState::default();
+}
+
+// vsync, fullscreen, the theme and the size of the interface are set by the settings
+fn prepare_ui(mut windows: ResMut<Windows>) {
     for window in windows.iter_mut() {
-        egui_context
-            .ctx_for_window_mut(window.id())
-            .set_visuals(egui::Visuals::dark());
         window.set_title("Inferno Engineer".to_string());
-        window.set_present_mode(bevy::window::PresentMode::AutoVsync);
         window.set_scale_factor_override(Some(1.0));
     }
 }
@@ -29,12 +40,29 @@ fn prepare_ui(mut egui_context: ResMut<EguiContext>, mut windows: ResMut<Windows
 fn game_ui(
     mut egui_context: ResMut<EguiContext>,
     mut ui_state: ResMut<UiState>,
+    app_state: Res<State<AppState>>,
     game_state: ResMut<game_backend::GameState>,
-    mut active_npc: ResMut<game_backend::ActiveNpc>,
-    command_events: EventWriter<game_backend::CommandExecutionEvent>,
-    npc_events: EventWriter<game_backend::NpcActionEvent>,
+    active_npc: Res<game_backend::ActiveNpc>,
+    speakers: Res<game_backend::Speakers>,
+    images: Res<Assets<Image>>,
+    locale: Res<locale::Locale>,
+    accessibility: Res<accessibility::Accessibility>,
+    mut command_events: EventWriter<game_backend::CommandExecutionEvent>,
+    mut npc_events: EventWriter<game_backend::NpcActionEvent>,
 ) {
     let mut is_terminal_open = ui_state.is_terminal_open;
+    // set again by the text boxes that are shown
+    ui_state.is_textbox_focused = false;
+
+    // item icons have to be registered to egui before they can be shown
+    let item_icons: HashMap<String, egui::TextureId> = game_state
+        .inventory
+        .iter()
+        .filter_map(|(id, _)| {
+            let item = game_state.inventory.get(id)?;
+            Some((id.to_string(), egui_context.add_image(item.icon_handle.to_owned())))
+        })
+        .collect();
 
     let (show_info, show_terminal) = {
         use game_backend::GameProgress::*;
@@ -47,7 +75,8 @@ fn game_ui(
 
     if show_info {
         // the info window
-        egui::Window::new("Info")
+        egui::Window::new(locale.tr("Info"))
+            .id(egui::Id::new("info"))
             .collapsible(true)
             .show(egui_context.ctx_mut(), |ui| {
                 // show a tab bar that allows selecting a panel
@@ -55,121 +84,380 @@ fn game_ui(
                     .resizable(false)
                     .show_inside(ui, |ui| {
                         ui.horizontal(|ui| {
-                            if ui.button("Dialogue").clicked() {
+                            if ui.button(locale.tr("Dialogue")).clicked() {
                                 ui_state.selected_tab = InfoTab::Dialogue;
                             }
-                            if ui.button("Details").clicked() {
+                            if ui.button(locale.tr("Details")).clicked() {
                                 ui_state.selected_tab = InfoTab::Details;
                             }
+                            if ui.button(locale.tr("Inventory")).clicked() {
+                                ui_state.selected_tab = InfoTab::Inventory;
+                            }
                             if show_terminal {
-                                ui.checkbox(&mut is_terminal_open, "Show terminal");
+                                let text = locale.tr("Show terminal");
+                                ui.checkbox(&mut is_terminal_open, text);
                             }
                         })
                     });
 
                 egui::CentralPanel::default().show_inside(ui, |ui| match ui_state.selected_tab {
-                    InfoTab::Dialogue => game_ui_dialogue(ui, ui_state.as_mut(), npc_events),
-                    InfoTab::Details => game_ui_details(ui, game_state.as_ref(), &mut active_npc),
+                    InfoTab::Dialogue => {
+                        game_ui_dialogue(ui, ui_state.as_mut(), &speakers, &locale, &accessibility)
+                    }
+                    InfoTab::Details => {
+                        game_ui_details(ui, game_state.as_ref(), &active_npc, &locale)
+                    }
+                    InfoTab::Inventory => game_ui_inventory(
+                        ui,
+                        ui_state.as_mut(),
+                        game_state.as_ref(),
+                        &item_icons,
+                        &locale,
+                        &mut command_events,
+                    ),
                 });
             });
     }
 
     if show_terminal {
         // the terminal window
-        egui::Window::new("Terminal")
+        egui::Window::new(locale.tr("Terminal"))
+            .id(egui::Id::new("terminal"))
             .collapsible(true)
             .open(&mut is_terminal_open)
             .show(egui_context.ctx_mut(), |ui| {
-                game_ui_terminal(ui, ui_state.as_mut(), game_state.as_ref(), command_events)
+                game_ui_terminal(
+                    ui,
+                    ui_state.as_mut(),
+                    game_state.as_ref(),
+                    &locale,
+                    &mut command_events,
+                )
             });
         ui_state.is_terminal_open = is_terminal_open;
     }
+
+    let is_talking = matches!(app_state.current(), AppState::Dialogue | AppState::Battle);
+    if is_talking && active_npc.0.is_some() {
+        // the portrait of the line being typed
+        let portrait = ui_state
+            .current_line()
+            .and_then(|line| {
+                let speaker = speakers.speakers.get(line.speaker.as_ref()?)?;
+                speaker.portrait(line.expression.as_deref())
+            })
+            .and_then(|handle| {
+                let size = images.get(handle)?.size();
+                let size = egui::vec2(size.x, size.y) * PORTRAIT_HEIGHT / size.y.max(1.0);
+                Some((egui_context.add_image(handle.to_owned()), size))
+            });
+        game_ui_dialogue_box(
+            egui_context.ctx_mut(),
+            ui_state.as_mut(),
+            &speakers,
+            &locale,
+            &accessibility,
+            portrait,
+            &mut npc_events,
+        );
+    }
 }
 
+// the backlog of everything that has been said, a page at a time
 fn game_ui_dialogue(
     ui: &mut egui::Ui,
     ui_state: &mut UiState,
-    mut npc_events: EventWriter<game_backend::NpcActionEvent>,
+    speakers: &game_backend::Speakers,
+    locale: &locale::Locale,
+    accessibility: &accessibility::Accessibility,
 ) {
+    let old_filter = (ui_state.backlog_filter.clone(), ui_state.backlog_search.clone());
+
+    ui.horizontal(|ui| {
+        // everyone who has said something, by the name they were last seen with
+        let mut filters = vec![BacklogFilter::Everyone, BacklogFilter::Player];
+        let mut names = HashMap::new();
+        for line in ui_state.transcript.iter() {
+            let Some(id) = &line.speaker else { continue; };
+            if !names.contains_key(id) {
+                filters.push(BacklogFilter::Npc(id.to_owned()));
+            }
+            if let Some(name) = &line.name {
+                names.insert(id.to_owned(), name.to_owned());
+            }
+        }
+        let label = |filter: &BacklogFilter| match filter {
+            BacklogFilter::Everyone => locale.tr("Everyone"),
+            BacklogFilter::Player => locale.tr("You"),
+            BacklogFilter::Npc(id) => names.get(id).unwrap_or(id).to_owned(),
+        };
+        egui::ComboBox::from_id_source("backlog_filter")
+            .selected_text(label(&ui_state.backlog_filter))
+            .show_ui(ui, |ui| {
+                for filter in filters {
+                    let text = label(&filter);
+                    ui.selectable_value(&mut ui_state.backlog_filter, filter, text);
+                }
+            });
+        let search = ui.add(
+            egui::TextEdit::singleline(&mut ui_state.backlog_search)
+                .hint_text(locale.tr("Search...")),
+        );
+        ui_state.is_textbox_focused |= search.has_focus();
+    });
+    if (ui_state.backlog_filter.clone(), ui_state.backlog_search.clone()) != old_filter {
+        ui_state.backlog_page = 0;
+    }
+
+    let search = ui_state.backlog_search.to_lowercase();
+    let lines: Vec<&TranscriptLine> = ui_state
+        .transcript
+        .iter()
+        .filter(|line| ui_state.backlog_filter.matches(line))
+        .filter(|line| search.is_empty() || line.text.to_lowercase().contains(&search))
+        .collect();
+    // the newest lines are on the first page
+    let page_count = ((lines.len() + BACKLOG_PAGE_LINES - 1) / BACKLOG_PAGE_LINES).max(1);
+    let page = ui_state.backlog_page.min(page_count - 1);
+    let end = lines.len() - page * BACKLOG_PAGE_LINES;
+    let start = end.saturating_sub(BACKLOG_PAGE_LINES);
+
+    ui.horizontal(|ui| {
+        let older = egui::Button::new(locale.tr("◀ Older"));
+        if ui.add_enabled(page + 1 < page_count, older).clicked() {
+            ui_state.backlog_page = page + 1;
+        }
+        ui.label(format!("{} / {}", page_count - page, page_count));
+        if ui.add_enabled(page > 0, egui::Button::new(locale.tr("Newer ▶"))).clicked() {
+            ui_state.backlog_page = page - 1;
+        }
+    });
+    ui.separator();
+
     egui::ScrollArea::vertical()
         .stick_to_bottom(true)
         .show(ui, |ui| {
-            for (name, text) in ui_state.dialogue.iter() {
-                ui.horizontal_wrapped(|ui| {
-                    if let Some(name) = name {
-                        let color = match name.as_str() {
-                            "You" => egui::Color32::from_rgb(80, 140, 242),
-                            _ => egui::Color32::from_rgb(226, 45, 42),
-                        };
-                        ui.label(
-                            egui::RichText::new(name)
-                                .color(color)
-                                .strong()
-                                .underline(),
-                        );
-                    }
-                    ui.label(text);
-                });
+            if lines.is_empty() {
+                ui.weak(locale.tr("Nothing has been said yet"));
             }
-
-            if ui_state.choices.is_empty() {
-                if ui.button("Next").clicked() {
-                    npc_events.send_default();
-                }
-            } else {
-                let mut chosen = false;
-                for (idx, choice) in ui_state.choices.iter().enumerate() {
-                    if ui.button(choice).clicked() {
-                        chosen = true;
-                        ui_state.dialogue.push((Some("You".to_string()), choice.to_owned()));
-                        npc_events.send(game_backend::NpcActionEvent(npcs::PlayerAction::Respond(
-                            idx,
-                        )));
-                    }
-                }
-                if chosen {
-                    ui_state.choices.clear();
+            for line in &lines[start..end] {
+                if let Some(name) = &line.name {
+                    // the player's name is translated when shown
+                    ui.label(
+                        egui::RichText::new(locale.tr(name))
+                            .color(name_color(speakers, line.speaker.as_ref(), accessibility))
+                            .strong()
+                            .underline(),
+                    );
                 }
+                ui.label(&line.text);
             }
         });
 }
 
+// the page being read at the bottom of the screen, with the portrait of the speaker
+fn game_ui_dialogue_box(
+    ctx: &egui::Context,
+    ui_state: &mut UiState,
+    speakers: &game_backend::Speakers,
+    locale: &locale::Locale,
+    accessibility: &accessibility::Accessibility,
+    portrait: Option<(egui::TextureId, egui::Vec2)>,
+    npc_events: &mut EventWriter<game_backend::NpcActionEvent>,
+) {
+    let Some(current) = ui_state.current_index() else { return; };
+    let width = (ctx.available_rect().width() - 40.0).min(DIALOGUE_BOX_WIDTH);
+
+    egui::Area::new("dialogue_box")
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
+        .show(ctx, |ui| {
+            egui::Frame::window(ui.style()).show(ui, |ui| {
+                ui.set_width(width);
+                ui.horizontal_top(|ui| {
+                    if let Some((texture, size)) = portrait {
+                        ui.image(texture, size);
+                    }
+                    ui.vertical(|ui| {
+                        // lines after the current one haven't been typed yet
+                        for line in ui_state.dialogue[..=current].iter_mut() {
+                            if let Some(name) = &line.name {
+                                let speaker = line.speaker.as_ref();
+                                let color = name_color(speakers, speaker, accessibility);
+                                ui.label(egui::RichText::new(name).color(color).strong());
+                            }
+                            if line.text.show(ui, !accessibility.reduced_motion).clicked() {
+                                line.text.skip();
+                            }
+                        }
+                        ui.add_space(8.0);
+                        if !ui_state.dialogue[current].text.is_done() {
+                            return;
+                        }
+                        if ui_state.choices.is_empty() {
+                            if ui.button(locale.tr("Next ▸")).clicked() {
+                                ui_state.next_page();
+                                npc_events.send_default();
+                            }
+                        } else if let Some(choice) = choice_menu(ui, ui_state) {
+                            ui_state.respond(choice);
+                            let action = npcs::PlayerAction::Respond(choice);
+                            npc_events.send(game_backend::NpcActionEvent(action));
+                        }
+                    });
+                });
+            });
+        });
+}
+
+// the choices as a menu, the one selected with the arrow keys is highlighted,
+// returns the one clicked
+fn choice_menu(ui: &mut egui::Ui, ui_state: &mut UiState) -> Option<usize> {
+    let mut chosen = None;
+    for (idx, choice) in ui_state.choices.iter().enumerate() {
+        let selected = idx == ui_state.selected_choice;
+        let text = format!("{}. {}", idx + 1, choice);
+        let label = ui.selectable_label(selected, text);
+        if label.hovered() {
+            ui_state.selected_choice = idx;
+        }
+        if label.clicked() {
+            chosen = Some(idx);
+        }
+    }
+    chosen
+}
+
 fn game_ui_details(
     ui: &mut egui::Ui,
     game_state: &game_backend::GameState,
     active_npc: &game_backend::ActiveNpc,
+    locale: &locale::Locale,
 ) {
+    // translated line by line, the numbers in them are filled in by patterns
+    let tr_lines = |text: &str| text.lines().map(|line| locale.tr(line)).collect::<Vec<_>>();
     egui::ScrollArea::vertical().show(ui, |ui| {
         ui.label(match &active_npc.0 {
-            Some(npc) => npc.info(),
-            None => "You are exploring by yourself".to_string(),
+            Some(npc) => tr_lines(&npc.info()).join("\n"),
+            None => locale.tr("You are exploring by yourself"),
         });
+        if !game_state.enemy_effects.is_empty() {
+            let status = format!("Status: {}", game_state.enemy_effects.describe());
+            ui.label(locale.tr(&status));
+        }
         ui.separator();
-        ui.label(game_state.player_details());
+        ui.label(tr_lines(&game_state.player_details()).join("\n"));
+    });
+}
+
+fn game_ui_inventory(
+    ui: &mut egui::Ui,
+    ui_state: &mut UiState,
+    game_state: &game_backend::GameState,
+    item_icons: &HashMap<String, egui::TextureId>,
+    locale: &locale::Locale,
+    command_events: &mut EventWriter<game_backend::CommandExecutionEvent>,
+) {
+    let inventory = &game_state.inventory;
+    if inventory.is_empty() {
+        ui.label(locale.tr("You don't have any items"));
+        return;
+    }
+
+    // the buttons run the same commands as the terminal
+    let mut command = None;
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::Grid::new("inventory").striped(true).show(ui, |ui| {
+            for (id, count) in inventory.iter() {
+                match item_icons.get(id) {
+                    Some(texture_id) => ui.image(*texture_id, [32.0, 32.0]),
+                    None => ui.label(""),
+                };
+                let equipped = inventory.is_equipped(id);
+                let mut text = format!("{} x{}", locale.tr(&inventory.item_name(id)), count);
+                if equipped {
+                    text.push_str(&locale.tr(" (equipped)"));
+                }
+                let label = ui.label(text);
+                let item = inventory.get(id);
+                if let Some(item) = item {
+                    label.on_hover_text(locale.tr(&item.description));
+                }
+                ui.horizontal(|ui| {
+                    match item.and_then(|item| item.slot) {
+                        Some(slot) if equipped => {
+                            if ui.button(locale.tr("Unequip")).clicked() {
+                                command = Some(format!("unequip {}", slot.name()));
+                            }
+                        }
+                        Some(_) => {
+                            if ui.button(locale.tr("Equip")).clicked() {
+                                command = Some(format!("equip {}", id));
+                            }
+                        }
+                        None => {
+                            if ui.button(locale.tr("Use")).clicked() {
+                                command = Some(format!("use {}", id));
+                            }
+                        }
+                    }
+                    if ui.button(locale.tr("Drop")).clicked() {
+                        command = Some(format!("drop {}", id));
+                    }
+                    if let Some(npc) = &game_state.encountered_npc {
+                        if ui.button(locale.tr("Give")).clicked() {
+                            command = Some(format!("give {} {}", npc, id));
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
     });
+    if let Some(command) = command {
+        ui_state.log_message(LogEntry::Command(command.to_owned()));
+        command_events.send(game_backend::CommandExecutionEvent(command));
+    }
 }
 
 fn game_ui_terminal(
     ui: &mut egui::Ui,
     ui_state: &mut UiState,
     game_state: &game_backend::GameState,
-    mut command_events: EventWriter<game_backend::CommandExecutionEvent>,
+    locale: &locale::Locale,
+    command_events: &mut EventWriter<game_backend::CommandExecutionEvent>,
 ) {
     // the input panel at the button
     egui::TopBottomPanel::bottom("input_panel")
         .resizable(false)
         .min_height(0.0)
         .show_inside(ui, |ui| {
+            // show the progress of the command that is running
+            if let Some(job) = game_state.jobs.foreground() {
+                let text = match job.progress() {
+                    Some(progress) => format!("{}  {:.0}%", job.command, progress * 100.0),
+                    None => job.command.to_owned(),
+                };
+                ui.add(
+                    egui::ProgressBar::new(job.progress().unwrap_or(0.0))
+                        .animate(job.progress().is_none())
+                        .text(text),
+                )
+                .on_hover_text(locale.tr("Press Ctrl+C to stop"));
+            }
             ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
                 // show a textbox for typing commands and a "run" button
-                let command_button = ui.button("Run");
+                let command_button = ui.button(locale.tr("Run"));
+                let copy_button = ui.button(locale.tr("Copy"));
+                if copy_button.on_hover_text(locale.tr("Copy the log")).clicked() {
+                    ui.output().copied_text = ui_state.get_log_string();
+                }
                 let command_input = ui.add_sized(
                     ui.available_size(),
                     egui::TextEdit::singleline(&mut ui_state.terminal_input)
                         .code_editor()
-                        .hint_text("Type a command..."),
+                        .hint_text(locale.tr("Type a command...")),
                 );
-                ui_state.is_textbox_focused = command_input.has_focus();
+                ui_state.is_textbox_focused |= command_input.has_focus();
 
                 // run the command when enter key or button is pressed
                 if command_button.clicked()
@@ -177,7 +465,7 @@ fn game_ui_terminal(
                 {
                     let command = ui_state.terminal_input.trim().to_string();
                     if !command.is_empty() {
-                        ui_state.log_message(format!(">>> {}", command));
+                        ui_state.log_message(LogEntry::Command(command.to_owned()));
                         ui_state.terminal_input.clear();
                         command_input.request_focus();
                         command_events.send(game_backend::CommandExecutionEvent(command));
@@ -223,8 +511,10 @@ fn game_ui_terminal(
                             }
                             None => {
                                 // otherwise show command completions (max 5)
-                                let mut completions =
-                                    commands::list_commands(command, game_state.player_level);
+                                let mut completions = commands::list_commands(
+                                    command,
+                                    game_state.effective_level(),
+                                );
                                 completions.sort();
                                 let mut completion: Option<&str> = None;
 
@@ -266,49 +556,406 @@ fn game_ui_terminal(
     egui::CentralPanel::default().show_inside(ui, |ui| {
         egui::ScrollArea::vertical()
             .stick_to_bottom(true)
+            .auto_shrink([false, false])
             .show(ui, |ui| {
-                let log_string = ui_state.get_log_string();
-                let mut log_str = log_string.as_str();
-                let log_text = egui::TextEdit::multiline(&mut log_str)
-                    .code_editor()
-                    .desired_width(f32::INFINITY);
-                ui.add_sized(ui.available_size(), log_text);
+                let mut clicked_link = None;
+                for (idx, entry) in ui_state.terminal_log.iter().enumerate() {
+                    match entry {
+                        LogEntry::Command(command) => {
+                            ui.label(egui::RichText::new(format!(">>> {}", command)).monospace());
+                        }
+                        LogEntry::Output(output) => {
+                            ui.push_id(idx, |ui| {
+                                if let Some(link) = game_ui_command_output(ui, output) {
+                                    clicked_link = Some(link);
+                                }
+                            });
+                            ui.add_space(ui.text_style_height(&egui::TextStyle::Monospace));
+                        }
+                    }
+                }
+
+                // clicking on a link runs the command it points to
+                if let Some(command) = clicked_link {
+                    ui_state.log_message(LogEntry::Command(command.to_owned()));
+                    command_events.send(game_backend::CommandExecutionEvent(command));
+                }
             });
     });
 }
 
+// shows styled command output, returns the command of the clicked link if any
+fn game_ui_command_output(ui: &mut egui::Ui, output: &commands::CommandOutput) -> Option<String> {
+    let mut clicked_link = None;
+    let mut show_span = |ui: &mut egui::Ui, span: &commands::Span| {
+        use commands::output::Severity;
+        use commands::SpanStyle::*;
+        let warning_color = egui::Color32::from_rgb(232, 184, 56);
+        let error_color = egui::Color32::from_rgb(226, 45, 42);
+        let text = egui::RichText::new(&span.text).monospace();
+        let text = match (span.style, output.severity) {
+            (Plain, Severity::Info) => text,
+            (Plain, Severity::Warning) | (Warning, _) => text.color(warning_color),
+            (Plain, Severity::Error) | (Error, _) => text.color(error_color),
+            (Strong, _) => text.strong(),
+            (Code, _) => text.color(egui::Color32::from_rgb(150, 200, 240)),
+            (Muted, _) => text.weak(),
+            (Success, _) => text.color(egui::Color32::from_rgb(96, 196, 96)),
+        };
+        match &span.link {
+            Some(link) => {
+                if ui.link(text).on_hover_text(link.as_str()).clicked() {
+                    clicked_link = Some(link.to_owned());
+                }
+            }
+            None => {
+                ui.label(text);
+            }
+        }
+    };
+
+    for (idx, block) in output.blocks.iter().enumerate() {
+        match block {
+            commands::output::Block::Line(spans) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    for span in spans.iter() {
+                        show_span(ui, span);
+                    }
+                });
+            }
+            commands::output::Block::Table { header, rows } => {
+                egui::Grid::new(idx).striped(true).show(ui, |ui| {
+                    if !header.is_empty() {
+                        for title in header.iter() {
+                            ui.label(egui::RichText::new(title).monospace().strong());
+                        }
+                        ui.end_row();
+                    }
+                    for row in rows.iter() {
+                        for cell in row.iter() {
+                            show_span(ui, cell);
+                        }
+                        ui.end_row();
+                    }
+                });
+            }
+        }
+    }
+    clicked_link
+}
+
+// command output is translated once when it is logged, manuals as a whole
+fn translate_output(
+    output: &commands::CommandOutput,
+    locale: &locale::Locale,
+) -> commands::CommandOutput {
+    let man_page = output.man_page.as_ref().and_then(|command| locale.man_page(command));
+    if let Some(page) = man_page {
+        return commands::man_page_output(page);
+    }
+    let mut output = output.to_owned();
+    for block in output.blocks.iter_mut() {
+        match block {
+            commands::output::Block::Line(spans) => *spans = translate_line(spans, locale),
+            commands::output::Block::Table { header, rows } => {
+                for title in header.iter_mut() {
+                    *title = locale.tr(title);
+                }
+                for span in rows.iter_mut().flatten() {
+                    span.text = locale.tr(&span.text);
+                }
+            }
+        }
+    }
+    output
+}
+
+// a line is translated as a whole, its styled spans and links fill the `{}` of the pattern,
+// so "You picked up {}" can put the item anywhere in the sentence
+fn translate_line(spans: &[commands::Span], locale: &locale::Locale) -> Vec<commands::Span> {
+    let is_value =
+        |span: &commands::Span| span.style != commands::SpanStyle::Plain || span.link.is_some();
+    let pattern: String = spans
+        .iter()
+        .map(|span| match is_value(span) {
+            true => "{}",
+            false => span.text.as_str(),
+        })
+        .collect();
+    let values: Vec<_> = spans.iter().filter(|span| is_value(span)).collect();
+    let translation = locale.tr(&pattern);
+    // lines without a translation of their own are translated a span at a time
+    if translation == pattern || translation.matches("{}").count() != values.len() {
+        return spans
+            .iter()
+            .map(|span| commands::Span {
+                text: locale.tr(&span.text),
+                ..span.to_owned()
+            })
+            .collect();
+    }
+    let mut line = vec![];
+    let mut pieces = translation.split("{}");
+    for value in values {
+        let piece = pieces.next().unwrap_or_default();
+        if !piece.is_empty() {
+            line.push(commands::Span::plain(piece));
+        }
+        line.push(commands::Span {
+            text: locale.tr(&value.text),
+            ..value.to_owned()
+        });
+    }
+    let rest: String = pieces.collect();
+    if !rest.is_empty() {
+        line.push(commands::Span::plain(rest));
+    }
+    line
+}
+
+// ctrl+c stops the command running in the terminal
+fn interrupt_command(
+    keyboard_input: Res<Input<KeyCode>>,
+    ui_state: Res<UiState>,
+    mut interrupt_events: EventWriter<game_backend::CommandInterruptEvent>,
+) {
+    if ui_state.is_terminal_open
+        && keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl])
+        && keyboard_input.just_pressed(KeyCode::C)
+    {
+        interrupt_events.send(game_backend::CommandInterruptEvent);
+    }
+}
+
 fn update_ui_events(
     mut ui_state: ResMut<UiState>,
+    speakers: Res<game_backend::Speakers>,
+    active_npc: Res<game_backend::ActiveNpc>,
+    locale: Res<locale::Locale>,
     mut command_events: EventReader<game_backend::CommandResultEvent>,
     mut npc_events: EventReader<game_backend::NpcResponseEvent>,
 ) {
-    for game_backend::CommandResultEvent(result) in command_events.iter() {
-        ui_state.log_message(result.to_owned() + "\n");
+    for game_backend::CommandResultEvent(output) in command_events.iter() {
+        ui_state.log_message(LogEntry::Output(translate_output(output, &locale)));
     }
     for game_backend::NpcResponseEvent(response) in npc_events.iter() {
         let npcs::NpcResponse {
             message,
             name,
             choices,
+            expression,
         } = response;
-        ui_state
-            .dialogue
-            .push((name.to_owned(), message.to_owned()));
-        ui_state.choices = choices.to_owned();
+        let speaker = active_npc.0.as_ref().map(|npc| npc.id().to_string());
+        let voice = match speaker.as_ref().and_then(|id| speakers.speakers.get(id)) {
+            Some(speaker) => speaker.voice.to_owned(),
+            None => typewriter::Voice::default(),
+        };
+        // another npc starts talking on a page of their own
+        let is_other_speaker = match ui_state.dialogue.first() {
+            Some(line) => line.speaker != speaker,
+            None => false,
+        };
+        if ui_state.new_page || is_other_speaker {
+            ui_state.dialogue.clear();
+            ui_state.new_page = false;
+        }
+        // battles go on without moving to the next page, the oldest lines make room
+        while ui_state.dialogue.len() >= MAX_PAGE_LINES && ui_state.dialogue[0].text.is_done() {
+            ui_state.dialogue.remove(0);
+        }
+
+        let text = typewriter::Typewriter::new(&locale.tr(message), &voice);
+        let name = name.as_ref().map(|name| locale.tr(name));
+        ui_state.log_dialogue(TranscriptLine {
+            speaker: speaker.to_owned(),
+            name: name.to_owned(),
+            text: text.plain_text(),
+        });
+        ui_state.dialogue.push(DialogueLine {
+            speaker,
+            name,
+            expression: expression.to_owned(),
+            text,
+        });
+        ui_state.choices = choices.iter().map(|choice| locale.tr(choice)).collect();
     }
 }
 
+// types out the lines of the page one after another
+fn type_dialogue(
+    time: Res<Time>,
+    settings: Res<settings::Settings>,
+    accessibility: Res<accessibility::Accessibility>,
+    mut ui_state: ResMut<UiState>,
+    mut sound_events: EventWriter<sound::SoundEvent>,
+) {
+    let Some(current) = ui_state.current_index() else { return; };
+    let line = &mut ui_state.dialogue[current];
+    if line.text.is_done() {
+        return;
+    }
+    // the whole line shows up at once
+    if accessibility.reduced_motion {
+        line.text.skip();
+        return;
+    }
+    if let Some(pitch) = line.text.update(time.delta_seconds() * settings.text_speed) {
+        sound_events.send(sound::SoundEvent(sound::Sound::Blip(pitch)));
+    }
+}
+
+// writes out the dialogue for `log export`
+fn export_log(
+    ui_state: Res<UiState>,
+    locale: Res<locale::Locale>,
+    mut export_events: EventReader<game_backend::ExportLogEvent>,
+    mut result_events: EventWriter<game_backend::CommandResultEvent>,
+) {
+    for _ in export_events.iter() {
+        let transcript = ui_state.get_transcript_string(&locale);
+        let output = match storage::export(LOG_FILE_NAME, &transcript) {
+            Ok(msg) => commands::CommandOutput::new().lines(&msg, commands::SpanStyle::Success),
+            Err(msg) => {
+                commands::CommandOutput::error(&format!("Error: Could not export: {}", msg))
+            }
+        };
+        result_events.send(game_backend::CommandResultEvent(output));
+    }
+}
+
+// space or enter shows the line being typed at once and then moves on, the choices are
+// picked with their number or with the arrow keys and space or enter.
+// the keys are left alone while the terminal or any other widget has keyboard focus. this
+// runs before the ui, so the enter that runs a command is still seen as typed in the terminal
+fn dialogue_keys(
+    mut egui_context: ResMut<EguiContext>,
+    keyboard_input: Res<Input<KeyCode>>,
+    app_state: Res<State<AppState>>,
+    active_npc: Res<game_backend::ActiveNpc>,
+    mut ui_state: ResMut<UiState>,
+    mut npc_events: EventWriter<game_backend::NpcActionEvent>,
+) {
+    let is_talking = matches!(app_state.current(), AppState::Dialogue | AppState::Battle);
+    let has_focus = egui_context.ctx_mut().memory().focus().is_some();
+    if !is_talking || active_npc.0.is_none() || has_focus || ui_state.is_textbox_focused {
+        return;
+    }
+    let Some(current) = ui_state.current_index() else { return; };
+    let advance = keyboard_input.any_just_pressed(ADVANCE_KEYS);
+
+    let line = &mut ui_state.dialogue[current];
+    if !line.text.is_done() {
+        if advance {
+            line.text.skip();
+        }
+        return;
+    }
+
+    if ui_state.choices.is_empty() {
+        if advance {
+            ui_state.next_page();
+            npc_events.send_default();
+        }
+        return;
+    }
+    let count = ui_state.choices.len();
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        ui_state.selected_choice = (ui_state.selected_choice + count - 1) % count;
+    }
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        ui_state.selected_choice = (ui_state.selected_choice + 1) % count;
+    }
+    let numbered = CHOICE_KEYS
+        .iter()
+        .position(|keys| keyboard_input.any_just_pressed(*keys))
+        .filter(|&choice| choice < count);
+    let chosen = match numbered {
+        Some(choice) => Some(choice),
+        None if advance => Some(ui_state.selected_choice.min(count - 1)),
+        None => None,
+    };
+    if let Some(choice) = chosen {
+        ui_state.respond(choice);
+        let action = npcs::PlayerAction::Respond(choice);
+        npc_events.send(game_backend::NpcActionEvent(action));
+    }
+}
+
+// the keys that move dialogue and cutscenes on
+pub const ADVANCE_KEYS: [KeyCode; 3] = [KeyCode::Space, KeyCode::Return, KeyCode::NumpadEnter];
+// the keys that pick the first nine choices
+const CHOICE_KEYS: [[KeyCode; 2]; 9] = [
+    [KeyCode::Key1, KeyCode::Numpad1],
+    [KeyCode::Key2, KeyCode::Numpad2],
+    [KeyCode::Key3, KeyCode::Numpad3],
+    [KeyCode::Key4, KeyCode::Numpad4],
+    [KeyCode::Key5, KeyCode::Numpad5],
+    [KeyCode::Key6, KeyCode::Numpad6],
+    [KeyCode::Key7, KeyCode::Numpad7],
+    [KeyCode::Key8, KeyCode::Numpad8],
+    [KeyCode::Key9, KeyCode::Numpad9],
+];
+// how many lines of a page the dialogue box shows at once
+const MAX_PAGE_LINES: usize = 3;
+const BACKLOG_PAGE_LINES: usize = 50;
+const LOG_FILE_NAME: &str = "dialogue-log.txt";
+const DIALOGUE_BOX_WIDTH: f32 = 900.0;
+const PORTRAIT_HEIGHT: f32 = 160.0;
+const PLAYER_NAME_COLOR: egui::Color32 = egui::Color32::from_rgb(80, 140, 242);
+
+#[derive(Clone)]
+enum LogEntry {
+    Command(String),
+    Output(commands::CommandOutput),
+}
+
 enum InfoTab {
     Dialogue,
     Details,
+    Inventory,
+}
+
+// a line in the dialogue box
+struct DialogueLine {
+    // the id of the npc who said it, none for the player
+    speaker: Option<String>,
+    name: Option<String>,
+    expression: Option<String>,
+    text: typewriter::Typewriter,
+}
+
+// a line in the backlog, without the markup
+struct TranscriptLine {
+    speaker: Option<String>,
+    name: Option<String>,
+    text: String,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+enum BacklogFilter {
+    Everyone,
+    Player,
+    Npc(String),
 }
 
 #[derive(Resource)]
 pub struct UiState {
     terminal_input: String,
-    terminal_log: Vec<String>,
-    dialogue: Vec<(Option<String>, String)>,
+    terminal_log: Vec<LogEntry>,
+    // the page shown in the dialogue box
+    dialogue: Vec<DialogueLine>,
+    // set when the player moves on, the next line starts a new page
+    new_page: bool,
+    transcript: Vec<TranscriptLine>,
+    // the lines that no longer fit in the backlog, they are still exported
+    forgotten: Vec<TranscriptLine>,
+    backlog_filter: BacklogFilter,
+    backlog_search: String,
+    // counted from the newest page
+    backlog_page: usize,
     choices: Vec<String>,
+    selected_choice: usize,
     selected_tab: InfoTab,
     is_terminal_open: bool,
     pub is_textbox_focused: bool,
@@ -318,12 +965,29 @@ impl Default for UiState {
     fn default() -> Self {
         UiState {
             terminal_input: String::new(),
-            terminal_log: vec![
-                "The Inferno Interactive Console v666".to_string(),
-                "Type `commands` for a list of commands or try `help <command>`.".to_string(),
-            ],
+            terminal_log: vec![LogEntry::Output(
+                commands::CommandOutput::new()
+                    .line(vec![commands::Span::styled(
+                        "The Inferno Interactive Console v666",
+                        commands::SpanStyle::Strong,
+                    )])
+                    .line(vec![
+                        commands::Span::plain("Type "),
+                        commands::Span::link("commands", "commands"),
+                        commands::Span::plain(" for a list of commands or try "),
+                        commands::Span::styled("help <command>", commands::SpanStyle::Code),
+                        commands::Span::plain("."),
+                    ]),
+            )],
             dialogue: vec![],
+            new_page: false,
+            transcript: vec![],
+            forgotten: vec![],
+            backlog_filter: BacklogFilter::Everyone,
+            backlog_search: String::new(),
+            backlog_page: 0,
             choices: vec![],
+            selected_choice: 0,
             selected_tab: InfoTab::Dialogue,
             is_terminal_open: false,
             is_textbox_focused: false,
@@ -331,17 +995,106 @@ impl Default for UiState {
     }
 }
 
+fn name_color(
+    speakers: &game_backend::Speakers,
+    speaker: Option<&String>,
+    accessibility: &accessibility::Accessibility,
+) -> egui::Color32 {
+    if accessibility.safe_name_colors {
+        // the player comes first, then the npcs in the order of their ids
+        let index = match speaker {
+            Some(id) => 1 + speakers.speakers.keys().filter(|other| *other < id).count(),
+            None => 0,
+        };
+        let colors = accessibility::SAFE_NAME_COLORS;
+        let (r, g, b) = colors[index % colors.len()];
+        return egui::Color32::from_rgb(r, g, b);
+    }
+    match speaker.and_then(|id| speakers.speakers.get(id)) {
+        Some(speaker) => {
+            let (r, g, b) = speaker.name_color;
+            egui::Color32::from_rgb(r, g, b)
+        }
+        None => PLAYER_NAME_COLOR,
+    }
+}
+
+impl BacklogFilter {
+    fn matches(&self, line: &TranscriptLine) -> bool {
+        match self {
+            BacklogFilter::Everyone => true,
+            BacklogFilter::Player => line.speaker.is_none(),
+            BacklogFilter::Npc(id) => line.speaker.as_ref() == Some(id),
+        }
+    }
+}
+
 impl UiState {
     const MAX_LOG_LINES: usize = 256;
-    fn log_message(&mut self, message: String) {
-        self.terminal_log.push(message);
+    const MAX_TRANSCRIPT_LINES: usize = 4096;
+    // the line of the page being typed, or the last one once the page is done
+    fn current_index(&self) -> Option<usize> {
+        let typing = self.dialogue.iter().position(|line| !line.text.is_done());
+        typing.or_else(|| self.dialogue.len().checked_sub(1))
+    }
+    fn current_line(&self) -> Option<&DialogueLine> {
+        self.dialogue.get(self.current_index()?)
+    }
+    fn next_page(&mut self) {
+        self.new_page = true;
+    }
+    // the player's answer goes to the backlog, not the dialogue box
+    fn respond(&mut self, choice: usize) {
+        if let Some(text) = self.choices.get(choice) {
+            let text = typewriter::Typewriter::revealed(text).plain_text();
+            self.log_dialogue(TranscriptLine {
+                speaker: None,
+                name: Some("You".to_string()),
+                text,
+            });
+        }
+        self.choices.clear();
+        self.selected_choice = 0;
+        self.next_page();
+    }
+    fn log_message(&mut self, entry: LogEntry) {
+        self.terminal_log.push(entry);
         // limit output to MAX_LOG_LINES messages
         if self.terminal_log.len() > UiState::MAX_LOG_LINES {
             self.terminal_log =
                 self.terminal_log[self.terminal_log.len() - UiState::MAX_LOG_LINES..].to_vec();
         }
     }
+    fn log_dialogue(&mut self, line: TranscriptLine) {
+        self.transcript.push(line);
+        // the oldest lines leave the backlog
+        if self.transcript.len() > UiState::MAX_TRANSCRIPT_LINES {
+            let excess = self.transcript.len() - UiState::MAX_TRANSCRIPT_LINES;
+            self.forgotten.extend(self.transcript.drain(..excess));
+        }
+    }
+    // everything that has been said, with the player's name translated
+    fn get_transcript_string(&self, locale: &locale::Locale) -> String {
+        self.forgotten
+            .iter()
+            .chain(self.transcript.iter())
+            .map(|line| match &line.name {
+                Some(name) => format!("{}: {}", locale.tr(name), line.text),
+                None => line.text.to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n"
+    }
+    // the log as plain text, without styles or links
     fn get_log_string(&self) -> String {
-        self.terminal_log.join("\n")
+        self.terminal_log
+            .iter()
+            .map(|entry| match entry {
+                LogEntry::Command(command) => format!(">>> {}", command),
+                LogEntry::Output(output) => output.plain_text() + "\n",
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
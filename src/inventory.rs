@@ -0,0 +1,368 @@
+use crate::{app_state::AppState, commands, game_backend, game_map, loading, status_effects};
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<ItemFile>::new(&["items.ron"]))
+            .init_resource::<ItemFileHandle>()
+            .init_resource::<Pickups>()
+            .add_startup_system(load_files)
+            .add_system(prepare_items)
+            .add_system(prepare_pickups)
+            .add_system(drop_items)
+            .add_system_set(SystemSet::on_update(AppState::Exploring).with_system(collect_pickups))
+            .add_system_set(SystemSet::on_enter(AppState::Title).with_system(reset_pickups));
+    }
+}
+
+fn load_files(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut item_handle: ResMut<ItemFileHandle>,
+) {
+    item_handle.0 = loading.load(&asset_server, "itemfile.items.ron");
+}
+
+fn prepare_items(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut game_state: ResMut<game_backend::GameState>,
+    item_handle: Res<ItemFileHandle>,
+    item_file: Res<Assets<ItemFile>>,
+) {
+    if game_state.inventory.loaded {
+        return;
+    }
+    let Some(item_file) = item_file.get(&item_handle.0) else { return; };
+
+    for (id, item) in item_file.items.iter() {
+        let mut item = item.clone();
+        item.icon_handle = loading.load(&asset_server, &item.icon);
+        game_state.inventory.catalog.insert(id.to_owned(), item);
+    }
+    game_state.inventory.loaded = true;
+}
+
+fn prepare_pickups(
+    mut commands: Commands,
+    map: Res<game_map::Map>,
+    game_state: Res<game_backend::GameState>,
+    mut pickups: ResMut<Pickups>,
+) {
+    if pickups.loaded || !map.loaded || !game_state.inventory.loaded {
+        return;
+    }
+    // a saved game has its own list of items left on the map
+    let list = pickups.saved.take().unwrap_or_else(|| map.pickups.clone());
+    for (item, location) in list.iter() {
+        pickups.spawn(&mut commands, &game_state.inventory, item, *location);
+    }
+    pickups.loaded = true;
+}
+
+fn reset_pickups(mut commands: Commands, mut pickups: ResMut<Pickups>) {
+    pickups.restore(&mut commands, None);
+}
+
+// picks up the item when the player walks onto its tile
+fn collect_pickups(
+    mut commands: Commands,
+    mut game_state: ResMut<game_backend::GameState>,
+    mut pickups: ResMut<Pickups>,
+    mut last_location: Local<(usize, usize)>,
+    mut result_events: EventWriter<game_backend::CommandResultEvent>,
+) {
+    let location = (game_state.player_x, game_state.player_y);
+    if !pickups.loaded || *last_location == location {
+        return;
+    }
+    *last_location = location;
+
+    while let Some(index) = pickups.list.iter().position(|p| p.location == location) {
+        let pickup = pickups.list.remove(index);
+        commands.entity(pickup.entity).despawn();
+        game_state.inventory.add(&pickup.item, 1);
+        let name = game_state.inventory.item_name(&pickup.item);
+        result_events.send(game_backend::CommandResultEvent(
+            commands::CommandOutput::new().line(vec![
+                commands::Span::plain("You picked up "),
+                commands::Span::link(name, "inventory"),
+            ]),
+        ));
+    }
+}
+
+// places items dropped by the `drop` command on the player's tile
+fn drop_items(
+    mut commands: Commands,
+    mut game_state: ResMut<game_backend::GameState>,
+    mut pickups: ResMut<Pickups>,
+) {
+    if !pickups.loaded || game_state.dropped_items.is_empty() {
+        return;
+    }
+    let location = (game_state.player_x, game_state.player_y);
+    for item in std::mem::take(&mut game_state.dropped_items) {
+        pickups.spawn(&mut commands, &game_state.inventory, &item, location);
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum ItemEffect {
+    Heal(i32),
+    MaxHp(i32),
+    Atk(i32),
+    Def(i32),
+    Level(i32),
+    Status(status_effects::StatusEffect),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Accessory,
+}
+
+// stat bonuses given by equipped items
+#[derive(Clone, Copy, Default, Deserialize)]
+pub struct StatModifiers {
+    #[serde(default)]
+    pub atk: i32,
+    #[serde(default)]
+    pub def: i32,
+    #[serde(default)]
+    pub max_hp: i32,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub description: String,
+    icon: String,
+    #[serde(default)]
+    pub effects: Vec<ItemEffect>,
+    #[serde(default)]
+    pub slot: Option<EquipSlot>,
+    #[serde(default)]
+    pub modifiers: StatModifiers,
+    #[serde(skip)]
+    pub icon_handle: Handle<Image>,
+}
+
+// the items the player carries, along with the definitions of all items
+#[derive(Default)]
+pub struct Inventory {
+    catalog: HashMap<String, Item>,
+    items: Vec<(String, usize)>,
+    equipment: HashMap<EquipSlot, String>,
+    pub loaded: bool,
+}
+
+#[derive(Deserialize, bevy::reflect::TypeUuid)]
+#[uuid = "5b0e5d3c-8a36-4c4f-b5d8-7f3b2a41e0c9"]
+struct ItemFile {
+    items: HashMap<String, Item>,
+}
+
+#[derive(Resource, Default)]
+struct ItemFileHandle(Handle<ItemFile>);
+
+struct Pickup {
+    item: String,
+    location: (usize, usize),
+    entity: Entity,
+}
+
+// items lying around on the map
+#[derive(Resource, Default)]
+pub struct Pickups {
+    list: Vec<Pickup>,
+    saved: Option<Vec<(String, (usize, usize))>>,
+    loaded: bool,
+}
+
+impl Pickups {
+    const Z_LAYER: f32 = 5.0;
+    // the items on the map and where they are, for saving
+    pub fn locations(&self) -> Vec<(String, (usize, usize))> {
+        self.list
+            .iter()
+            .map(|pickup| (pickup.item.to_owned(), pickup.location))
+            .collect()
+    }
+    // removes every item from the map, they are placed again from the save or the map file
+    pub fn restore(
+        &mut self,
+        commands: &mut Commands,
+        saved: Option<Vec<(String, (usize, usize))>>,
+    ) {
+        for pickup in self.list.drain(..) {
+            commands.entity(pickup.entity).despawn();
+        }
+        self.saved = saved;
+        self.loaded = false;
+    }
+    fn spawn(
+        &mut self,
+        commands: &mut Commands,
+        inventory: &Inventory,
+        item: &str,
+        location: (usize, usize),
+    ) {
+        let Some(definition) = inventory.get(item) else { return; };
+        let (x, y) = location;
+        let entity = commands
+            .spawn(SpriteBundle {
+                transform: Transform::from_xyz(
+                    (x as f32 + 0.5) * game_map::Tile::WIDTH,
+                    (y as f32 + 0.5) * game_map::Tile::HEIGHT,
+                    Pickups::Z_LAYER,
+                ),
+                texture: definition.icon_handle.to_owned(),
+                ..default()
+            })
+            .id();
+        self.list.push(Pickup {
+            item: item.to_string(),
+            location,
+            entity,
+        });
+    }
+}
+
+impl Inventory {
+    pub fn get(&self, id: &str) -> Option<&Item> {
+        self.catalog.get(id)
+    }
+    pub fn item_name(&self, id: &str) -> String {
+        match self.catalog.get(id) {
+            Some(item) => item.name.to_owned(),
+            None => id.to_string(),
+        }
+    }
+    // finds a carried item by its id or name
+    pub fn find(&self, name: &str) -> Option<String> {
+        let name = name.trim().to_lowercase();
+        self.items
+            .iter()
+            .map(|(id, _)| id)
+            .find(|id| **id == name || self.item_name(id).to_lowercase() == name.replace('_', " "))
+            .cloned()
+    }
+    pub fn add(&mut self, id: &str, count: usize) {
+        match self.items.iter_mut().find(|(item, _)| item == id) {
+            Some((_, current)) => *current += count,
+            None => self.items.push((id.to_string(), count)),
+        }
+    }
+    // removes one of the item, returns false if there is none
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(index) = self.items.iter().position(|(item, _)| item == id) else {
+            return false;
+        };
+        self.items[index].1 -= 1;
+        if self.items[index].1 == 0 {
+            self.items.remove(index);
+            self.equipment.retain(|_, item| item != id);
+        }
+        true
+    }
+    // equips a carried item, returns the item previously in the same slot
+    pub fn equip(&mut self, id: &str) -> Result<Option<String>, String> {
+        let Some(item) = self.catalog.get(id) else {
+            return Err(format!("Unknown item: {}", id));
+        };
+        let Some(slot) = item.slot else {
+            return Err(format!("{} can't be equipped", item.name));
+        };
+        if !self.items.iter().any(|(item, _)| item == id) {
+            return Err(format!("You don't have {}", item.name));
+        }
+        Ok(self.equipment.insert(slot, id.to_string()))
+    }
+    pub fn unequip(&mut self, slot: EquipSlot) -> Option<String> {
+        self.equipment.remove(&slot)
+    }
+    pub fn equipped(&self, slot: EquipSlot) -> Option<&str> {
+        self.equipment.get(&slot).map(|id| id.as_str())
+    }
+    pub fn is_equipped(&self, id: &str) -> bool {
+        self.equipment.values().any(|item| item == id)
+    }
+    // the sum of the modifiers of all equipped items
+    pub fn bonus(&self) -> StatModifiers {
+        let mut bonus = StatModifiers::default();
+        for id in self.equipment.values() {
+            if let Some(item) = self.catalog.get(id) {
+                bonus.atk += item.modifiers.atk;
+                bonus.def += item.modifiers.def;
+                bonus.max_hp += item.modifiers.max_hp;
+            }
+        }
+        bonus
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.items.iter().map(|(id, count)| (id.as_str(), *count))
+    }
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 3] = [EquipSlot::Weapon, EquipSlot::Armor, EquipSlot::Accessory];
+    pub fn name(&self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "weapon",
+            EquipSlot::Armor => "armor",
+            EquipSlot::Accessory => "accessory",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<EquipSlot> {
+        EquipSlot::ALL
+            .into_iter()
+            .find(|slot| slot.name() == name.trim().to_lowercase())
+    }
+}
+
+impl ItemEffect {
+    // applies the effect and describes what happened
+    pub fn apply(&self, game_state: &mut game_backend::GameState) -> String {
+        match *self {
+            ItemEffect::Heal(amount) => {
+                let hitpoints =
+                    (game_state.player_hitpoints + amount).min(game_state.effective_max_hp());
+                let healed = hitpoints - game_state.player_hitpoints;
+                game_state.player_hitpoints = hitpoints;
+                format!("HP +{}", healed)
+            }
+            ItemEffect::MaxHp(amount) => {
+                game_state.player_max_hp += amount;
+                game_state.clamp_hitpoints();
+                format!("Max HP {:+}", amount)
+            }
+            ItemEffect::Atk(amount) => {
+                game_state.player_atk += amount;
+                format!("ATK {:+}", amount)
+            }
+            ItemEffect::Def(amount) => {
+                game_state.player_def += amount;
+                format!("DEF {:+}", amount)
+            }
+            ItemEffect::Level(amount) => {
+                game_state.player_level += amount;
+                format!("Access level {:+}", amount)
+            }
+            ItemEffect::Status(effect) => {
+                game_state.player_effects.apply(effect);
+                effect.kind.name().to_string()
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{animation, app_state::AppState, enemy_ai, game_backend, game_map};
+use bevy::asset::{Asset, LoadState};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingAssets>()
+            .add_event::<ReloadEvent>()
+            .add_system_set(
+                SystemSet::on_update(AppState::Loading)
+                    .with_system(check_loading)
+                    .with_system(loading_ui.after(check_loading)),
+            );
+    }
+}
+
+// starts the game once every tracked asset is loaded and every file has been read
+fn check_loading(
+    asset_server: Res<AssetServer>,
+    map: Res<game_map::Map>,
+    npcs: Res<game_backend::Npcs>,
+    cgs: Res<game_backend::Cgs>,
+    animations: Res<animation::Animations>,
+    behaviors: Res<enemy_ai::Behaviors>,
+    game_state: Res<game_backend::GameState>,
+    mut loading: ResMut<LoadingAssets>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    let states: Vec<(String, LoadState)> = loading
+        .handles
+        .iter()
+        .map(|(path, handle)| (path.to_owned(), asset_server.get_load_state(handle.id)))
+        .collect();
+    let loaded = states
+        .iter()
+        .filter(|(_, state)| *state == LoadState::Loaded)
+        .count();
+    loading.progress = loaded as f32 / states.len().max(1) as f32;
+    loading.failed = states
+        .into_iter()
+        .filter(|(_, state)| *state == LoadState::Failed)
+        .map(|(path, _)| path)
+        .collect();
+
+    let handles = loading.handles.iter().map(|(_, handle)| handle.id);
+    let files_read = map.loaded
+        && npcs.loaded
+        && cgs.loaded
+        && animations.loaded
+        && behaviors.loaded
+        && game_state.inventory.loaded;
+    if files_read && asset_server.get_group_load_state(handles) == LoadState::Loaded {
+        let _ = app_state.set(AppState::Title);
+    }
+}
+
+fn loading_ui(mut egui_context: ResMut<EguiContext>, loading: Res<LoadingAssets>) {
+    egui::CentralPanel::default().show(egui_context.ctx_mut(), |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 3.0);
+            ui.heading("Loading...");
+            ui.add(
+                egui::ProgressBar::new(loading.progress)
+                    .desired_width(400.0)
+                    .show_percentage(),
+            );
+            if !loading.failed.is_empty() {
+                ui.add_space(10.0);
+                ui.colored_label(
+                    egui::Color32::from_rgb(226, 45, 42),
+                    "Some files could not be loaded:",
+                );
+                for path in loading.failed.iter() {
+                    ui.monospace(path);
+                }
+            }
+        });
+    });
+}
+
+// sent when a resource read from a file changed outside of the game, because the file was
+// edited on disk or in the map editor, so whatever was spawned from it has to be replaced
+pub enum ReloadEvent {
+    Map,
+    Npcs,
+    Cgs,
+}
+
+// every asset the game has to wait for before it starts
+#[derive(Resource, Default)]
+pub struct LoadingAssets {
+    handles: Vec<(String, HandleUntyped)>,
+    progress: f32,
+    failed: Vec<String>,
+}
+
+impl LoadingAssets {
+    // loads an asset and keeps track of it until the game starts
+    pub fn load<T: Asset>(&mut self, asset_server: &AssetServer, path: &str) -> Handle<T> {
+        let handle: Handle<T> = asset_server.load(path);
+        // the npc and item files are prepared again for every new game, their assets are only
+        // tracked once
+        if !self.handles.iter().any(|(tracked, _)| tracked == path) {
+            self.handles.push((path.to_string(), handle.clone_untyped()));
+        }
+        handle
+    }
+}
@@ -0,0 +1,164 @@
+use crate::loading;
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(RonAssetPlugin::<LocaleFile>::new(&["locale.ron"]))
+            .init_resource::<Locale>()
+            .add_startup_system(load_files)
+            .add_system(apply_language);
+    }
+}
+
+fn load_files(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut locale: ResMut<Locale>,
+) {
+    // english is written in the source and needs no file
+    for language in Language::ALL.into_iter().filter(|&language| language != Language::English) {
+        let path = format!("locales/{}.locale.ron", language.code());
+        locale.files.push((language, loading.load(&asset_server, &path)));
+    }
+}
+
+// picks up the strings of the chosen language, again when its file changes
+fn apply_language(
+    mut locale: ResMut<Locale>,
+    locale_files: Res<Assets<LocaleFile>>,
+    mut asset_events: EventReader<AssetEvent<LocaleFile>>,
+) {
+    let modified = asset_events
+        .iter()
+        .any(|event| matches!(event, AssetEvent::Created { .. } | AssetEvent::Modified { .. }));
+    if !modified && locale.applied == Some(locale.language) {
+        return;
+    }
+
+    let language = locale.language;
+    let file = locale
+        .files
+        .iter()
+        .find(|(file_language, _)| *file_language == language)
+        .and_then(|(_, handle)| locale_files.get(handle));
+    let (strings, man_pages) = match file {
+        Some(file) => (file.strings.to_owned(), file.man_pages.to_owned()),
+        None if language == Language::English => Default::default(),
+        // still loading
+        None => return,
+    };
+    // strings with `{}` in them are tried one by one when there is no exact match,
+    // a string that is nothing but `{}` would match everything
+    let mut sources: Vec<_> = strings
+        .keys()
+        .filter(|source| source.contains("{}") && !source.replace("{}", "").is_empty())
+        .collect();
+    // the one with the most text around its `{}` goes first, so that the same one always wins
+    sources.sort_by_key(|source| (std::cmp::Reverse(source.replace("{}", "").len()), *source));
+    locale.patterns = sources
+        .into_iter()
+        .map(|source| {
+            let parts = source.split("{}").map(str::to_string).collect();
+            (parts, strings[source].to_owned())
+        })
+        .collect();
+    locale.strings = strings;
+    locale.man_pages = man_pages;
+    locale.applied = Some(language);
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    TraditionalChinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::TraditionalChinese];
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::TraditionalChinese => "zh-TW",
+        }
+    }
+    // the name of the language in itself
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::TraditionalChinese => "繁體中文",
+        }
+    }
+}
+
+// translations of the english text written in the source, by the english text
+#[derive(Deserialize, bevy::reflect::TypeUuid)]
+#[uuid = "3e1f7c52-8a94-4d6b-b0e2-5c7a9d1f4b86"]
+struct LocaleFile {
+    #[serde(default)]
+    strings: HashMap<String, String>,
+    // whole manual pages, by command name
+    #[serde(default)]
+    man_pages: HashMap<String, String>,
+}
+
+#[derive(Resource, Default)]
+pub struct Locale {
+    pub language: Language,
+    // the language the strings below are for
+    applied: Option<Language>,
+    strings: HashMap<String, String>,
+    // the pieces of a source string between its `{}`, and the translation
+    patterns: Vec<(Vec<String>, String)>,
+    man_pages: HashMap<String, String>,
+    files: Vec<(Language, Handle<LocaleFile>)>,
+}
+
+impl Locale {
+    // translates english text, text without a translation is shown as it is.
+    // the text filling the `{}` of a pattern is translated too, such as the message in
+    // "Error: {}"
+    pub fn tr(&self, text: &str) -> String {
+        if let Some(translation) = self.strings.get(text) {
+            return translation.to_owned();
+        }
+        for (parts, translation) in self.patterns.iter() {
+            if let Some(values) = match_pattern(parts, text) {
+                let mut result = String::new();
+                let mut pieces = translation.split("{}");
+                result.push_str(pieces.next().unwrap_or_default());
+                let values = values.iter().map(|value| self.tr(value));
+                for (piece, value) in pieces.zip(values.chain(std::iter::repeat(String::new()))) {
+                    result.push_str(&value);
+                    result.push_str(piece);
+                }
+                return result;
+            }
+        }
+        text.to_string()
+    }
+    // the manual of a command in the chosen language, if it has been translated
+    pub fn man_page(&self, command: &str) -> Option<&str> {
+        self.man_pages.get(command).map(String::as_str)
+    }
+}
+
+// the text filling the `{}` between the parts, if the text has all of the parts
+fn match_pattern<'a>(parts: &[String], text: &'a str) -> Option<Vec<&'a str>> {
+    let (first, rest) = parts.split_first()?;
+    let (last, middle) = rest.split_last()?;
+    let mut text = text.strip_prefix(first.as_str())?.strip_suffix(last.as_str())?;
+    let mut values = vec![];
+    for part in middle {
+        let index = text.find(part.as_str())?;
+        values.push(&text[..index]);
+        text = &text[index + part.len()..];
+    }
+    values.push(text);
+    Some(values)
+}
@@ -1,21 +1,59 @@
+mod accessibility;
+mod animation;
+mod app_state;
 mod canvas;
 mod commands;
+mod debug;
+mod enemy_ai;
+mod fonts;
 mod game_backend;
 mod game_frontend;
 mod game_map;
 mod game_ui;
+mod inventory;
+mod loading;
+mod locale;
+mod map_editor;
+mod menus;
+mod minimap;
 mod npcs;
+mod settings;
+mod sound;
+mod status_effects;
+mod storage;
+mod tilemap;
+mod typewriter;
 
 use bevy::prelude::*;
 use bevy_egui::EguiPlugin;
 
 pub fn main() {
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            watch_for_changes: cfg!(feature = "hot-reload"),
+            ..default()
+        }))
         .add_plugin(EguiPlugin)
+        .add_plugin(app_state::AppStatePlugin)
+        .add_plugin(loading::LoadingPlugin)
         .add_plugin(game_backend::GameBackendPlugin)
         .add_plugin(game_map::MapPlugin)
+        .add_plugin(tilemap::TilemapPlugin)
         .add_plugin(game_frontend::GameFrontendPlugin)
         .add_plugin(game_ui::GameUiPlugin)
+        .add_plugin(inventory::InventoryPlugin)
+        .add_plugin(status_effects::StatusEffectsPlugin)
+        .add_plugin(enemy_ai::EnemyAiPlugin)
+        .add_plugin(animation::AnimationPlugin)
+        .add_plugin(menus::MenusPlugin)
+        .add_plugin(map_editor::MapEditorPlugin)
+        .add_plugin(debug::DebugPlugin)
+        .add_plugin(sound::SoundPlugin)
+        .add_plugin(storage::StoragePlugin)
+        .add_plugin(locale::LocalePlugin)
+        .add_plugin(fonts::FontsPlugin)
+        .add_plugin(accessibility::AccessibilityPlugin)
+        .add_plugin(settings::SettingsPlugin)
+        .add_plugin(minimap::MinimapPlugin)
         .run();
 }
@@ -0,0 +1,279 @@
+use crate::{app_state::AppState, game_backend, game_map, loading};
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+pub struct MapEditorPlugin;
+
+impl Plugin for MapEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorState>()
+            .add_system_set(
+                SystemSet::on_update(AppState::Editor)
+                    .with_system(editor_ui)
+                    .with_system(move_camera)
+                    .with_system(paint.after(editor_ui)),
+            )
+            .add_system_set(SystemSet::on_exit(AppState::Editor).with_system(reset_zoom));
+        if cfg!(feature = "editor") {
+            app.add_system(toggle_editor);
+        }
+    }
+}
+
+// f2 opens the editor while walking around and closes it again
+fn toggle_editor(keyboard_input: Res<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if !keyboard_input.just_pressed(KeyCode::F2) {
+        return;
+    }
+    let _ = match app_state.current() {
+        AppState::Exploring => app_state.push(AppState::Editor),
+        AppState::Editor => app_state.pop(),
+        _ => Ok(()),
+    };
+}
+
+fn editor_ui(
+    mut egui_context: ResMut<EguiContext>,
+    windows: Res<Windows>,
+    map: Res<game_map::Map>,
+    map_handle: Res<game_map::MapFileHandle>,
+    map_files: Res<Assets<game_map::MapFile>>,
+    npcs: Res<game_backend::Npcs>,
+    npc_handle: Res<game_backend::NpcFileHandle>,
+    npc_files: Res<Assets<game_backend::NpcFile>>,
+    mut editor_state: ResMut<EditorState>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+) {
+    let ctx = egui_context.ctx_mut();
+    let Ok(camera) = camera_query.get_single() else { return; };
+    let hovered = hovered_tile(&windows, camera).filter(|&(x, y)| x < map.width && y < map.height);
+
+    egui::Window::new("Map Editor")
+        .default_pos((10.0, 10.0))
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.radio_value(&mut editor_state.brush, Brush::Road, "Road");
+            ui.radio_value(&mut editor_state.brush, Brush::Wall, "Wall");
+            ui.radio_value(&mut editor_state.brush, Brush::Start, "Start position");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut editor_state.brush, Brush::Npc, "NPC");
+                let mut ids: Vec<&String> = npcs.npcs.keys().collect();
+                ids.sort();
+                egui::ComboBox::from_id_source("npc")
+                    .selected_text(editor_state.npc.as_str())
+                    .show_ui(ui, |ui| {
+                        for id in ids {
+                            ui.selectable_value(&mut editor_state.npc, id.to_owned(), id.as_str());
+                        }
+                    });
+            });
+            ui.separator();
+
+            match hovered {
+                Some((x, y)) => {
+                    let tile_type = map.tiles[x][y].tile_type;
+                    ui.label(format!("Tile: ({}, {}), type {}", x, y, tile_type))
+                }
+                None => ui.label("Tile: -"),
+            };
+            ui.label(format!("Start: ({}, {})", map.start_pos.0, map.start_pos.1));
+            ui.label("WASD to move, scroll to zoom, F2 to close");
+            ui.separator();
+
+            if cfg!(target_arch = "wasm32") {
+                ui.label("Saving is only available in the native build");
+            } else if ui.button("Save").clicked() {
+                let map_file = map_files.get(&map_handle.0);
+                let npc_file = npc_files.get(&npc_handle.0);
+                editor_state.status = match (map_file, npc_file) {
+                    (Some(map_file), Some(npc_file)) => {
+                        let npc_file = npc_file.with_locations(&npcs);
+                        match save_files(&map.to_file(map_file), &npc_file) {
+                            Ok(()) => "Saved".to_string(),
+                            Err(msg) => format!("Could not save: {}", msg),
+                        }
+                    }
+                    _ => "The map is still loading".to_string(),
+                };
+            }
+            if !editor_state.status.is_empty() {
+                ui.label(&editor_state.status);
+            }
+        });
+
+    // mark the start position and the tile under the cursor on the map
+    let Some(window) = windows.get_primary() else { return; };
+    let to_screen = |(x, y): (f32, f32)| {
+        let (transform, projection) = camera;
+        let world = Vec2::new(x * game_map::Tile::WIDTH, y * game_map::Tile::HEIGHT);
+        let size = Vec2::new(window.width(), window.height());
+        let screen = (world - transform.translation.truncate()) / projection.scale + size / 2.0;
+        egui::pos2(screen.x, size.y - screen.y)
+    };
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    let color = egui::Color32::from_rgb(255, 200, 60);
+    let start = (map.start_pos.0 as f32 + 0.5, map.start_pos.1 as f32 + 0.5);
+    painter.circle_filled(to_screen(start), 8.0, color);
+    painter.text(
+        to_screen(start) - egui::vec2(0.0, 10.0),
+        egui::Align2::CENTER_BOTTOM,
+        "start",
+        egui::FontId::proportional(16.0),
+        color,
+    );
+    if let Some((x, y)) = hovered {
+        let (x, y) = (x as f32, y as f32);
+        let rect = egui::Rect::from_two_pos(to_screen((x, y)), to_screen((x + 1.0, y + 1.0)));
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(2.0, color));
+    }
+}
+
+// paints with the selected brush where the map is clicked
+fn paint(
+    mut egui_context: ResMut<EguiContext>,
+    windows: Res<Windows>,
+    mouse_input: Res<Input<MouseButton>>,
+    editor_state: Res<EditorState>,
+    mut map: ResMut<game_map::Map>,
+    mut npcs: ResMut<game_backend::Npcs>,
+    mut reload_events: EventWriter<loading::ReloadEvent>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+) {
+    if !map.loaded || egui_context.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+    let Ok(camera) = camera_query.get_single() else { return; };
+    let Some(tile) = hovered_tile(&windows, camera) else { return; };
+    if tile.0 >= map.width || tile.1 >= map.height {
+        return;
+    }
+
+    match editor_state.brush {
+        // roads and walls can be painted by dragging
+        Brush::Road | Brush::Wall => {
+            let is_road = editor_state.brush == Brush::Road;
+            if mouse_input.pressed(MouseButton::Left) && map.is_road(tile) != is_road {
+                map.set_road(tile, is_road);
+                reload_events.send(loading::ReloadEvent::Map);
+            }
+        }
+        Brush::Start => {
+            if mouse_input.just_pressed(MouseButton::Left) && map.is_road(tile) {
+                map.start_pos = tile;
+            }
+        }
+        Brush::Npc => {
+            if !mouse_input.just_pressed(MouseButton::Left) || !map.is_road(tile) {
+                return;
+            }
+            if let Some(npc) = npcs.npcs.get_mut(&editor_state.npc) {
+                npc.location = tile;
+                reload_events.send(loading::ReloadEvent::Npcs);
+            }
+        }
+    }
+}
+
+fn move_camera(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut egui_context: ResMut<EguiContext>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    let Ok((mut transform, mut projection)) = camera_query.get_single_mut() else { return; };
+    let ctx = egui_context.ctx_mut();
+
+    if !ctx.wants_keyboard_input() {
+        let mut direction = Vec2::ZERO;
+        if keyboard_input.any_pressed([KeyCode::A, KeyCode::Left]) {
+            direction.x -= 1.0;
+        }
+        if keyboard_input.any_pressed([KeyCode::D, KeyCode::Right]) {
+            direction.x += 1.0;
+        }
+        if keyboard_input.any_pressed([KeyCode::S, KeyCode::Down]) {
+            direction.y -= 1.0;
+        }
+        if keyboard_input.any_pressed([KeyCode::W, KeyCode::Up]) {
+            direction.y += 1.0;
+        }
+        let step = direction * CAMERA_VELOCITY * projection.scale * time.delta_seconds();
+        transform.translation += step.extend(0.0);
+    }
+
+    let scroll: f32 = wheel_events.iter().map(|event| event.y.signum()).sum();
+    if scroll != 0.0 && !ctx.is_pointer_over_area() {
+        projection.scale = (projection.scale * ZOOM_STEP.powf(-scroll)).clamp(0.5, 8.0);
+    }
+}
+
+fn reset_zoom(mut camera_query: Query<&mut OrthographicProjection, With<Camera>>) {
+    for mut projection in camera_query.iter_mut() {
+        projection.scale = 1.0;
+    }
+}
+
+// the tile under the mouse cursor, which may be outside of the map
+fn hovered_tile(
+    windows: &Windows,
+    (transform, projection): (&Transform, &OrthographicProjection),
+) -> Option<(usize, usize)> {
+    let window = windows.get_primary()?;
+    let cursor = window.cursor_position()?;
+    let size = Vec2::new(window.width(), window.height());
+    let world = transform.translation.truncate() + (cursor - size / 2.0) * projection.scale;
+    if world.x < 0.0 || world.y < 0.0 {
+        return None;
+    }
+    Some((
+        (world.x / game_map::Tile::WIDTH) as usize,
+        (world.y / game_map::Tile::HEIGHT) as usize,
+    ))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_files(
+    map_file: &game_map::MapFile,
+    npc_file: &game_backend::NpcFile,
+) -> Result<(), String> {
+    let config = ron::ser::PrettyConfig::default();
+    let map_text = ron::ser::to_string_pretty(map_file, config.clone());
+    let npc_text = ron::ser::to_string_pretty(npc_file, config);
+    std::fs::write(MAP_PATH, map_text.map_err(|err| err.to_string())?)
+        .map_err(|err| err.to_string())?;
+    std::fs::write(NPC_PATH, npc_text.map_err(|err| err.to_string())?)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_files(_: &game_map::MapFile, _: &game_backend::NpcFile) -> Result<(), String> {
+    Err("files can't be written in the browser".to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const MAP_PATH: &str = "assets/mapfile.map.ron";
+#[cfg(not(target_arch = "wasm32"))]
+const NPC_PATH: &str = "assets/npcfile.npcs.ron";
+// pixels per second at the default zoom
+const CAMERA_VELOCITY: f32 = 1200.0;
+const ZOOM_STEP: f32 = 1.25;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum Brush {
+    #[default]
+    Road,
+    Wall,
+    Start,
+    Npc,
+}
+
+#[derive(Resource, Default)]
+struct EditorState {
+    brush: Brush,
+    // the npc moved by the npc brush
+    npc: String,
+    // the result of the last save
+    status: String,
+}
@@ -0,0 +1,161 @@
+use crate::{app_state::AppState, fonts, game_backend, inventory, locale, settings, storage};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+pub struct MenusPlugin;
+
+impl Plugin for MenusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuState>()
+            .add_system(toggle_pause)
+            .add_system(quit_to_title)
+            .add_system_set(SystemSet::on_enter(AppState::Title).with_system(refresh_title))
+            .add_system_set(SystemSet::on_update(AppState::Title).with_system(title_menu))
+            .add_system_set(SystemSet::on_update(AppState::Paused).with_system(pause_menu))
+            .add_system_set(SystemSet::on_update(AppState::GameOver).with_system(game_over_menu));
+    }
+}
+
+fn refresh_title(mut menu_state: ResMut<MenuState>) {
+    menu_state.has_save = storage::has_save();
+}
+
+fn title_menu(
+    mut commands: Commands,
+    mut egui_context: ResMut<EguiContext>,
+    mut menu_state: ResMut<MenuState>,
+    mut game_state: ResMut<game_backend::GameState>,
+    mut npcs: ResMut<game_backend::Npcs>,
+    mut pickups: ResMut<inventory::Pickups>,
+    mut settings_menu: ResMut<settings::SettingsMenu>,
+    locale: Res<locale::Locale>,
+    fonts: Res<fonts::Fonts>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    menu_window(&locale.tr("Inferno Engineer")).show(egui_context.ctx_mut(), |ui| {
+        ui.vertical_centered_justified(|ui| {
+            if ui.button(locale.tr("New Game")).clicked() {
+                // a transition may already be queued in this frame, the click is then dropped
+                let _ = app_state.set(AppState::Cutscene);
+            }
+            let continue_button = egui::Button::new(locale.tr("Continue"));
+            if ui.add_enabled(menu_state.has_save, continue_button).clicked() {
+                match storage::load() {
+                    Some(save) => {
+                        if app_state.set(AppState::Exploring).is_ok() {
+                            save.restore(&mut commands, &mut game_state, &mut npcs, &mut pickups);
+                        }
+                    }
+                    None => menu_state.has_save = false,
+                }
+            }
+            if ui.button(locale.tr("Settings")).clicked() {
+                settings_menu.open = !settings_menu.open;
+            }
+        });
+        // the game runs without them, but some text is left undrawn
+        if !fonts.missing.is_empty() {
+            ui.add_space(10.0);
+            let warning = locale.tr("Some fonts could not be loaded:");
+            ui.colored_label(egui::Color32::from_rgb(232, 184, 56), warning);
+            for path in fonts.missing.iter() {
+                ui.monospace(path);
+            }
+            if fonts.text.is_none() {
+                ui.label(locale.tr("Captions in the game world are hidden without their font."));
+            }
+        }
+    });
+}
+
+// escape pauses the game and resumes it again
+fn toggle_pause(
+    keyboard_input: Res<Input<KeyCode>>,
+    menu_state: Res<MenuState>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Escape) || menu_state.quitting {
+        return;
+    }
+    // a transition may already be queued in this frame, e.g. when an encounter starts
+    let _ = if app_state.current().is_playing() {
+        app_state.push(AppState::Paused)
+    } else if *app_state.current() == AppState::Paused {
+        app_state.pop()
+    } else {
+        Ok(())
+    };
+}
+
+fn pause_menu(
+    mut egui_context: ResMut<EguiContext>,
+    mut menu_state: ResMut<MenuState>,
+    mut settings_menu: ResMut<settings::SettingsMenu>,
+    locale: Res<locale::Locale>,
+    mut app_state: ResMut<State<AppState>>,
+    mut save_events: EventWriter<storage::SaveGameEvent>,
+) {
+    if menu_state.quitting {
+        return;
+    }
+    menu_window(&locale.tr("Paused")).show(egui_context.ctx_mut(), |ui| {
+        ui.vertical_centered_justified(|ui| {
+            if ui.button(locale.tr("Resume")).clicked() {
+                let _ = app_state.pop();
+            }
+            if ui.button(locale.tr("Save")).clicked() {
+                save_events.send(storage::SaveGameEvent);
+            }
+            if ui.button(locale.tr("Settings")).clicked() {
+                settings_menu.open = !settings_menu.open;
+            }
+            if ui.button(locale.tr("Quit to Title")).clicked() {
+                menu_state.quitting = true;
+            }
+        });
+    });
+}
+
+// leaves the paused states one at a time before going to the title, replacing the whole stack
+// would skip their exit handlers
+fn quit_to_title(mut menu_state: ResMut<MenuState>, mut app_state: ResMut<State<AppState>>) {
+    if !menu_state.quitting {
+        return;
+    }
+    // only one transition can be queued in a frame, the next step is taken in the next one
+    if !app_state.inactives().is_empty() {
+        let _ = app_state.pop();
+    } else if app_state.set(AppState::Title).is_ok() {
+        menu_state.quitting = false;
+    }
+}
+
+fn game_over_menu(
+    mut egui_context: ResMut<EguiContext>,
+    locale: Res<locale::Locale>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    menu_window(&locale.tr("Game Over")).show(egui_context.ctx_mut(), |ui| {
+        ui.vertical_centered_justified(|ui| {
+            ui.label(locale.tr("You have been deallocated."));
+            if ui.button(locale.tr("Back to Title")).clicked() {
+                let _ = app_state.set(AppState::Title);
+            }
+        });
+    });
+}
+
+fn menu_window(title: &str) -> egui::Window<'static> {
+    egui::Window::new(title)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(200.0)
+}
+
+#[derive(Resource, Default)]
+struct MenuState {
+    has_save: bool,
+    // quit to title was chosen in the pause menu
+    quitting: bool,
+}
@@ -0,0 +1,196 @@
+use crate::{app_state, app_state::AppState, game_backend, game_map, game_ui, locale};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MapView>()
+            .add_system_set(SystemSet::on_enter(AppState::Title).with_system(reset_map_view))
+            .add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(app_state::playing)
+                    .with_system(explore)
+                    .with_system(toggle_map)
+                    .with_system(map_ui.after(explore).after(toggle_map)),
+            );
+    }
+}
+
+fn reset_map_view(mut map_view: ResMut<MapView>) {
+    *map_view = MapView::default();
+}
+
+// the tiles around the player are revealed as they walk
+fn explore(
+    map: Res<game_map::Map>,
+    mut game_state: ResMut<game_backend::GameState>,
+    mut last_tile: Local<Option<(usize, usize)>>,
+) {
+    let tile = (game_state.player_x, game_state.player_y);
+    if !map.loaded || (*last_tile == Some(tile) && game_state.explored.contains(&tile)) {
+        return;
+    }
+    *last_tile = Some(tile);
+    let (x, y) = tile;
+    for x in x.saturating_sub(EXPLORE_RADIUS)..=x + EXPLORE_RADIUS {
+        for y in y.saturating_sub(EXPLORE_RADIUS)..=y + EXPLORE_RADIUS {
+            if x < map.width && y < map.height {
+                game_state.explored.insert((x, y));
+            }
+        }
+    }
+}
+
+// m opens and closes the map, unless something is being typed
+fn toggle_map(
+    mut egui_context: ResMut<EguiContext>,
+    keyboard_input: Res<Input<KeyCode>>,
+    ui_state: Res<game_ui::UiState>,
+    mut map_view: ResMut<MapView>,
+) {
+    if ui_state.is_textbox_focused || egui_context.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::M) {
+        map_view.fullscreen = !map_view.fullscreen;
+    }
+}
+
+// the minimap in the corner, or the whole map in the middle of the screen
+fn map_ui(
+    mut egui_context: ResMut<EguiContext>,
+    map: Res<game_map::Map>,
+    game_state: Res<game_backend::GameState>,
+    npcs: Res<game_backend::Npcs>,
+    locale: Res<locale::Locale>,
+    mut map_view: ResMut<MapView>,
+) {
+    if !map.loaded || map.width == 0 || map.height == 0 {
+        return;
+    }
+    let ctx = egui_context.ctx_mut();
+    let aspect = map.height as f32 / map.width as f32;
+
+    if !map_view.fullscreen {
+        egui::Area::new("minimap")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-10.0, 10.0))
+            .show(ctx, |ui| {
+                egui::Frame::window(ui.style()).show(ui, |ui| {
+                    let size = egui::vec2(MINIMAP_WIDTH, MINIMAP_WIDTH * aspect);
+                    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+                    draw_map(ui.painter(), rect, &map, &game_state, &npcs);
+                    if response.on_hover_text(locale.tr("Map (M)")).clicked() {
+                        map_view.fullscreen = true;
+                    }
+                });
+            });
+        return;
+    }
+
+    let screen = ctx.screen_rect();
+    let width = (screen.width() * 0.85).min(screen.height() * 0.75 / aspect);
+    egui::Area::new("world_map")
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::window(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(locale.tr("Map"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(locale.tr("Close (M)")).clicked() {
+                            map_view.fullscreen = false;
+                        }
+                    });
+                });
+                let size = egui::vec2(width, width * aspect);
+                let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                draw_map(ui.painter(), rect, &map, &game_state, &npcs);
+                ui.horizontal(|ui| {
+                    for (color, name) in [
+                        (PLAYER_COLOR, "You"),
+                        (NPC_COLOR, "People"),
+                        (QUEST_COLOR, "Quest"),
+                    ] {
+                        let size = egui::vec2(12.0, 12.0);
+                        let (marker, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                        ui.painter().circle_filled(marker.center(), 5.0, color);
+                        ui.label(locale.tr(name));
+                        ui.add_space(10.0);
+                    }
+                });
+            });
+        });
+}
+
+// the explored roads with markers for the player and the npcs, quests show through the fog
+fn draw_map(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    map: &game_map::Map,
+    game_state: &game_backend::GameState,
+    npcs: &game_backend::Npcs,
+) {
+    let cell = rect.width() / map.width as f32;
+    // rows are counted from the bottom of the map
+    let center = |(x, y): (usize, usize)| {
+        rect.left_top()
+            + egui::vec2((x as f32 + 0.5) * cell, (map.height - y) as f32 * cell - 0.5 * cell)
+    };
+    let painter = painter.with_clip_rect(rect);
+    painter.rect_filled(rect, 0.0, FOG_COLOR);
+
+    let road = egui::Stroke::new((cell * 0.4).max(1.0), ROAD_COLOR);
+    // the bits of a tile type are its roads to the right, up, left and down
+    let directions = [
+        (1, egui::vec2(1.0, 0.0)),
+        (2, egui::vec2(0.0, -1.0)),
+        (4, egui::vec2(-1.0, 0.0)),
+        (8, egui::vec2(0.0, 1.0)),
+    ];
+    for &(x, y) in game_state.explored.iter() {
+        let Some(tile) = map.tiles.get(x).and_then(|column| column.get(y)) else { continue; };
+        let tile_rect = egui::Rect::from_center_size(center((x, y)), egui::vec2(cell, cell));
+        painter.rect_filled(tile_rect, 0.0, GROUND_COLOR);
+        if tile.tile_type == 0 {
+            continue;
+        }
+        let from = center((x, y));
+        for (bit, direction) in directions {
+            if tile.tile_type & bit != 0 {
+                painter.line_segment([from, from + direction * cell * 0.5], road);
+            }
+        }
+        painter.circle_filled(from, road.width / 2.0, ROAD_COLOR);
+    }
+
+    let radius = (cell * 0.4).max(2.5);
+    for npc in npcs.npcs.values() {
+        if npc.quest {
+            painter.circle_filled(center(npc.location), radius, QUEST_COLOR);
+            painter.circle_stroke(center(npc.location), radius * 2.0, (1.5, QUEST_COLOR));
+        } else if game_state.explored.contains(&npc.location) {
+            painter.circle_filled(center(npc.location), radius, NPC_COLOR);
+        }
+    }
+    let player = center((game_state.player_x, game_state.player_y));
+    painter.circle(player, radius * 1.3, PLAYER_COLOR, (1.5, egui::Color32::WHITE));
+}
+
+#[derive(Resource, Default)]
+pub struct MapView {
+    // the whole map is shown instead of the minimap
+    pub fullscreen: bool,
+}
+
+// how many tiles around the player are revealed
+const EXPLORE_RADIUS: usize = 2;
+const MINIMAP_WIDTH: f32 = 192.0;
+const FOG_COLOR: egui::Color32 = egui::Color32::from_rgb(12, 10, 14);
+const GROUND_COLOR: egui::Color32 = egui::Color32::from_rgb(52, 30, 28);
+const ROAD_COLOR: egui::Color32 = egui::Color32::from_rgb(200, 180, 160);
+// told apart with any kind of color blindness, also by their shapes
+const PLAYER_COLOR: egui::Color32 = egui::Color32::from_rgb(86, 180, 233);
+const NPC_COLOR: egui::Color32 = egui::Color32::from_rgb(213, 94, 0);
+const QUEST_COLOR: egui::Color32 = egui::Color32::from_rgb(240, 228, 66);
@@ -55,6 +55,14 @@ impl npcs::Npc for AliceNpc {
                 self.hitpoints -= damage;
             }
             npcs::PlayerAction::Respond(choice) => self.interact(game_state, Some(*choice)),
+            npcs::PlayerAction::Give(item) => {
+                // the name is left as it is so that it can be translated
+                let item = game_state.inventory.item_name(item);
+                self.message_queue.push_back(npc_response!(
+                    format!("a {}? for me? i'm a god, i don't need gifts. but thanks!", item),
+                    self.name()
+                ));
+            }
         }
     }
     fn get_response(&mut self) -> Option<npcs::NpcResponse> {
@@ -98,13 +106,13 @@ impl AliceNpc {
             }
             3 => {
                 self.message_queue.push_back(npc_response!(
-                    "hmm. i suppose there's this possibility -",
+                    "hmm.{pause=0.5} i suppose there's this possibility -",
                     self.name()
                 ));
             }
             4 => {
                 self.message_queue.push_back(npc_response!(
-                    "that you have just died.";
+                    "that you have {color=red}just died{/color}.";
                     "What?", "Wait I remember! There was a truck..."
                 ));
             }
@@ -126,7 +134,8 @@ impl AliceNpc {
                 if choice == 0 {
                     self.message_queue.push_back(npc_response!(
                         "i'm sorry, human. but i have some bad news. you have just died."
-                    ));
+                    )
+                    .with_expression("sad"));
                 }
             }
             7 => {
@@ -159,8 +168,11 @@ impl AliceNpc {
                     self.message_queue
                         .push_back(npc_response!("cool!!!", self.name()));
                 } else {
-                    self.message_queue
-                        .push_back(npc_response!("liars will be burning in hell!", self.name()));
+                    self.message_queue.push_back(npc_response!(
+                        "{shake}liars will be burning in hell!{/shake}",
+                        self.name()
+                    )
+                    .with_expression("angry"));
                 }
             }
             12 => {
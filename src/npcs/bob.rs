@@ -0,0 +1,106 @@
+use crate::npc_response;
+use crate::{game_backend, npcs};
+use npcs::Npc;
+use std::collections::VecDeque;
+
+pub struct BobNpc {
+    progress: usize,
+    hitpoints: i32,
+    message_queue: VecDeque<npcs::NpcResponse>,
+}
+
+impl Default for BobNpc {
+    fn default() -> Self {
+        BobNpc {
+            progress: 0,
+            hitpoints: BobNpc::MAX_HP,
+            message_queue: VecDeque::new(),
+        }
+    }
+}
+
+impl npcs::Npc for BobNpc {
+    fn id(&self) -> &'static str {
+        "bob"
+    }
+    fn name(&self) -> &'static str {
+        "Segfault"
+    }
+    fn info(&self) -> String {
+        [
+            "Segfault".to_string(),
+            "A bug that escaped from the kernel of hell.".to_string(),
+            "It dereferences everything it touches.".to_string(),
+            format!("HP: {} / {}", self.hitpoints.max(0), BobNpc::MAX_HP),
+        ]
+        .join("\n")
+    }
+    fn handle_action(
+        &mut self,
+        action: &npcs::PlayerAction,
+        game_state: &mut game_backend::GameState,
+    ) -> () {
+        match action {
+            npcs::PlayerAction::Ping | npcs::PlayerAction::Respond(_) => self.interact(),
+            npcs::PlayerAction::Attack(damage) => {
+                if self.hitpoints <= 0 {
+                    return;
+                }
+                self.hitpoints -= damage;
+                if self.hitpoints <= 0 {
+                    self.message_queue
+                        .push_back(npc_response!("core {speed=0.3}dumped...{/speed}", self.name()));
+                    game_state.player_level += 1;
+                    self.message_queue.push_back(npc_response!(
+                        "(the bug is fixed. your access level increased by 1.)"
+                    ));
+                }
+            }
+            npcs::PlayerAction::Give(_) => {
+                self.message_queue.push_back(npc_response!(
+                    "0x0000000000000000. that is what i think of your gift.",
+                    self.name()
+                )
+                .with_expression("angry"));
+            }
+        }
+    }
+    fn get_response(&mut self) -> Option<npcs::NpcResponse> {
+        self.message_queue.pop_front()
+    }
+    fn job_completed(&self) -> bool {
+        self.hitpoints <= 0 && self.message_queue.is_empty()
+    }
+    fn hitpoints(&self) -> Option<(i32, i32)> {
+        Some((self.hitpoints.max(0), BobNpc::MAX_HP))
+    }
+    fn heal(&mut self, amount: i32) {
+        if self.hitpoints > 0 {
+            self.hitpoints = (self.hitpoints + amount).min(BobNpc::MAX_HP);
+        }
+    }
+}
+
+impl BobNpc {
+    const MAX_HP: i32 = 30;
+    fn interact(&mut self) {
+        match self.progress {
+            0 => {
+                self.message_queue.push_back(
+                    npc_response!("{shake}SIGSEGV! SIGSEGV!{/shake}", self.name())
+                        .with_expression("angry"),
+                );
+                self.message_queue.push_back(npc_response!(
+                    "(a wild bug appears. it looks like it wants to crash you.)"
+                ));
+            }
+            1 => {
+                self.message_queue.push_back(npc_response!(
+                    "(maybe a `fireball` would fix it?)"
+                ));
+            }
+            _ => return,
+        }
+        self.progress += 1;
+    }
+}
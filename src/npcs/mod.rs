@@ -1,4 +1,5 @@
 mod alice;
+mod bob;
 
 use crate::game_backend;
 
@@ -13,22 +14,29 @@ pub trait Npc: Sync + Send {
     ) -> ();
     fn get_response(&mut self) -> Option<NpcResponse>;
     fn job_completed(&self) -> bool;
+    // current and max HP, or None if the npc can't be fought
+    fn hitpoints(&self) -> Option<(i32, i32)> {
+        None
+    }
+    fn heal(&mut self, _amount: i32) {}
 }
 
 pub fn get_npc_by_id(name: &str) -> Option<Box<dyn Npc>> {
     let name = name.trim();
     match name.to_lowercase().as_str() {
         "alice" => Some(Box::new(alice::AliceNpc::default())),
+        "bob" => Some(Box::new(bob::BobNpc::default())),
         _ => None,
     }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Default)]
 pub enum PlayerAction {
     #[default]
     Ping,
     Respond(usize),
     Attack(i32),
+    Give(String),
 }
 
 #[derive(Clone)]
@@ -36,6 +44,15 @@ pub struct NpcResponse {
     pub message: String,
     pub name: Option<String>,
     pub choices: Vec<String>,
+    // the portrait of the speaker shown with the message
+    pub expression: Option<String>,
+}
+
+impl NpcResponse {
+    pub fn with_expression(mut self, expression: &str) -> NpcResponse {
+        self.expression = Some(expression.to_string());
+        self
+    }
 }
 
 #[macro_export]
@@ -45,6 +62,7 @@ macro_rules! npc_response {
             message: String::from($msg),
             name: None,
             choices: vec![],
+            expression: None,
         }
     };
     ($msg:expr, $name:expr) => {
@@ -52,6 +70,7 @@ macro_rules! npc_response {
             message: String::from($msg),
             name: Some(String::from($name)),
             choices: vec![],
+            expression: None,
         }
     };
     ($msg:expr; $($resp:expr),+ $(,)?) => {
@@ -59,6 +78,7 @@ macro_rules! npc_response {
             message: String::from($msg),
             name: None,
             choices: vec![$($resp.to_string()),+],
+            expression: None,
         }
     };
     ($msg:expr, $name:expr; $($resp:expr),+ $(,)?) => {
@@ -66,6 +86,7 @@ macro_rules! npc_response {
             message: String::from($msg),
             name: Some(String::from($name)),
             choices: vec![$($resp.to_string()),+],
+            expression: None,
         }
     };
 }
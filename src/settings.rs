@@ -0,0 +1,242 @@
+use crate::{accessibility, app_state::AppState, locale, sound, storage};
+use bevy::prelude::*;
+use bevy::window::{PresentMode, WindowMode};
+use bevy_egui::{egui, EguiContext};
+use serde::{Deserialize, Serialize};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load())
+            .init_resource::<SettingsMenu>()
+            .add_system(apply_settings)
+            .add_system(settings_menu)
+            .add_system_set(SystemSet::on_exit(AppState::Title).with_system(close_menu))
+            .add_system_set(SystemSet::on_exit(AppState::Paused).with_system(close_menu));
+    }
+}
+
+// hands the settings to whatever uses them whenever they change
+fn apply_settings(
+    settings: Res<Settings>,
+    mut windows: ResMut<Windows>,
+    mut volumes: ResMut<sound::Volumes>,
+    mut locale: ResMut<locale::Locale>,
+    mut accessibility: ResMut<accessibility::Accessibility>,
+    mut applied_window: Local<Option<(bool, bool)>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    // the window is only touched when vsync or fullscreen change, not when a slider moves
+    let window_settings = (settings.vsync, settings.fullscreen);
+    if *applied_window != Some(window_settings) {
+        *applied_window = Some(window_settings);
+        for window in windows.iter_mut() {
+            window.set_present_mode(if settings.vsync {
+                PresentMode::AutoVsync
+            } else {
+                PresentMode::AutoNoVsync
+            });
+            // the browser decides how large the canvas is
+            if cfg!(not(target_arch = "wasm32")) {
+                window.set_mode(if settings.fullscreen {
+                    WindowMode::BorderlessFullscreen
+                } else {
+                    WindowMode::Windowed
+                });
+            }
+        }
+    }
+    // only set when different, as the resources would be seen as changed otherwise
+    if *volumes != settings.volumes {
+        *volumes = settings.volumes.clone();
+    }
+    if locale.language != settings.language {
+        locale.language = settings.language;
+    }
+    if *accessibility != settings.accessibility {
+        *accessibility = settings.accessibility.clone();
+    }
+}
+
+fn settings_menu(
+    mut egui_context: ResMut<EguiContext>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut settings: ResMut<Settings>,
+    locale: Res<locale::Locale>,
+) {
+    // kept for the next time the game starts once nothing is being dragged, rather than on
+    // every step of a slider. this also runs after the menu is closed
+    if settings_menu.unsaved && !egui_context.ctx_mut().input().pointer.any_down() {
+        settings_menu.unsaved = false;
+        if let Err(msg) = settings.save() {
+            warn!("could not save the settings: {}", msg);
+        }
+    }
+    if !settings_menu.open {
+        return;
+    }
+    // edited on a copy, so that the settings are only applied and saved when they change
+    let mut edited = settings.clone();
+    let mut open = settings_menu.open;
+    egui::Window::new(locale.tr("Settings"))
+        .id(egui::Id::new("settings"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(egui_context.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                for tab in SettingsTab::ALL {
+                    ui.selectable_value(&mut settings_menu.tab, tab, locale.tr(tab.name()));
+                }
+            });
+            ui.separator();
+            match settings_menu.tab {
+                SettingsTab::Video => {
+                    ui.checkbox(&mut edited.vsync, locale.tr("Vertical sync"));
+                    if cfg!(not(target_arch = "wasm32")) {
+                        ui.checkbox(&mut edited.fullscreen, locale.tr("Fullscreen"));
+                    }
+                }
+                SettingsTab::Audio => {
+                    let volumes = &mut edited.volumes;
+                    for (volume, name) in [
+                        (&mut volumes.master, "Master"),
+                        (&mut volumes.music, "Music"),
+                        (&mut volumes.ambience, "Ambience"),
+                        (&mut volumes.effects, "Effects"),
+                    ] {
+                        ui.add(egui::Slider::new(volume, 0.0..=1.0).text(locale.tr(name)));
+                    }
+                }
+                SettingsTab::Gameplay => {
+                    egui::ComboBox::from_label(locale.tr("Language"))
+                        .selected_text(edited.language.name())
+                        .show_ui(ui, |ui| {
+                            for language in locale::Language::ALL {
+                                let name = language.name();
+                                ui.selectable_value(&mut edited.language, language, name);
+                            }
+                        });
+                    let slider = egui::Slider::new(&mut edited.text_speed, Settings::TEXT_SPEEDS);
+                    ui.add(slider.text(locale.tr("Text speed")));
+                    let slider = egui::Slider::new(&mut edited.movement_speed, Settings::SPEEDS);
+                    ui.add(slider.text(locale.tr("Movement speed")));
+                    let slider = egui::Slider::new(&mut edited.animation_speed, Settings::SPEEDS);
+                    ui.add(slider.text(locale.tr("Animation speed")));
+                }
+                SettingsTab::Accessibility => {
+                    edited.accessibility.show(ui, |text| locale.tr(text));
+                }
+            }
+        });
+    settings_menu.open = open;
+    if edited != *settings {
+        *settings = edited;
+        settings_menu.unsaved = true;
+    }
+}
+
+fn close_menu(mut settings_menu: ResMut<SettingsMenu>) {
+    settings_menu.open = false;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum SettingsTab {
+    #[default]
+    Video,
+    Audio,
+    Gameplay,
+    Accessibility,
+}
+
+impl SettingsTab {
+    const ALL: [SettingsTab; 4] = [
+        SettingsTab::Video,
+        SettingsTab::Audio,
+        SettingsTab::Gameplay,
+        SettingsTab::Accessibility,
+    ];
+    fn name(&self) -> &'static str {
+        match self {
+            SettingsTab::Video => "Video",
+            SettingsTab::Audio => "Audio",
+            SettingsTab::Gameplay => "Gameplay",
+            SettingsTab::Accessibility => "Accessibility",
+        }
+    }
+}
+
+// the settings window, opened from the title screen and the pause menu
+#[derive(Resource, Default)]
+pub struct SettingsMenu {
+    pub open: bool,
+    tab: SettingsTab,
+    // the settings changed since they were last saved
+    unsaved: bool,
+}
+
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub vsync: bool,
+    // native only, the game always fills the canvas on the web
+    pub fullscreen: bool,
+    // how fast dialogue is typed out, multiplies the speed of each voice
+    pub text_speed: f32,
+    // multiplies how fast the player walks
+    pub movement_speed: f32,
+    // multiplies the frame rate of every animation
+    pub animation_speed: f32,
+    pub language: locale::Language,
+    pub volumes: sound::Volumes,
+    pub accessibility: accessibility::Accessibility,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            vsync: true,
+            fullscreen: false,
+            text_speed: 1.0,
+            movement_speed: 1.0,
+            animation_speed: 1.0,
+            language: locale::Language::default(),
+            volumes: sound::Volumes::default(),
+            accessibility: accessibility::Accessibility::default(),
+        }
+    }
+}
+
+const SETTINGS_KEY: &str = "inferno-engineer-settings";
+// where the accessibility options were kept before they became part of the settings
+const OLD_ACCESSIBILITY_KEY: &str = "inferno-engineer-accessibility";
+
+impl Settings {
+    const TEXT_SPEEDS: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+    const SPEEDS: std::ops::RangeInclusive<f32> = 0.5..=2.0;
+
+    fn load() -> Settings {
+        let saved: Option<Settings> =
+            storage::read(SETTINGS_KEY).and_then(|text| ron::from_str(&text).ok());
+        let Some(text) = storage::read(OLD_ACCESSIBILITY_KEY) else {
+            return saved.unwrap_or_default();
+        };
+        // the old options are moved into the settings, unless there already are settings
+        let settings = saved.unwrap_or_else(|| Settings {
+            accessibility: ron::from_str(&text).unwrap_or_default(),
+            ..default()
+        });
+        match settings.save() {
+            Ok(()) => storage::remove(OLD_ACCESSIBILITY_KEY),
+            Err(msg) => warn!("could not save the settings: {}", msg),
+        }
+        settings
+    }
+    fn save(&self) -> Result<(), String> {
+        let text = ron::to_string(self).map_err(|err| err.to_string())?;
+        storage::write(SETTINGS_KEY, &text)
+    }
+}
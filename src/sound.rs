@@ -0,0 +1,276 @@
+use crate::{app_state::AppState, game_backend, game_ui, loading};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoundAssets>()
+            .init_resource::<Volumes>()
+            .init_resource::<MusicPlayer>()
+            .add_event::<SoundEvent>()
+            .add_startup_system(load_files)
+            .add_system(play_music)
+            .add_system(play_ambience)
+            .add_system(queue_sounds.before(play_sounds))
+            .add_system(play_sounds);
+    }
+}
+
+fn load_files(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<loading::LoadingAssets>,
+    mut sound_assets: ResMut<SoundAssets>,
+) {
+    let mut load = |path: &str| loading.load(&asset_server, &format!("sounds/{}.wav", path));
+    *sound_assets = SoundAssets {
+        title: load("music/title"),
+        explore: load("music/explore"),
+        battle: load("music/battle"),
+        ambience: load("ambience"),
+        footstep: load("footstep"),
+        blip: load("blip"),
+        keystroke: load("keystroke"),
+        fireball: load("fireball"),
+    };
+}
+
+// crossfades to the music of the current state
+fn play_music(
+    time: Res<Time>,
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    app_state: Res<State<AppState>>,
+    sound_assets: Res<SoundAssets>,
+    volumes: Res<Volumes>,
+    mut music_player: ResMut<MusicPlayer>,
+) {
+    let paused = *app_state.current() == AppState::Paused;
+    // the music of the state below the pause menu keeps playing
+    let state = match app_state.inactives().last() {
+        Some(state) if paused => state,
+        _ => app_state.current(),
+    };
+    let music = match state {
+        AppState::Loading => None,
+        AppState::Title | AppState::GameOver => Some(Music::Title),
+        AppState::Battle => Some(Music::Battle),
+        _ => Some(Music::Explore),
+    };
+
+    if music != music_player.current {
+        music_player.current = music;
+        // a track that is still fading out fades back in instead
+        let is_playing = |music| music_player.tracks.iter().any(|track| track.music == music);
+        if let Some(music) = music.filter(|&music| !is_playing(music)) {
+            let source = match music {
+                Music::Title => &sound_assets.title,
+                Music::Explore => &sound_assets.explore,
+                Music::Battle => &sound_assets.battle,
+            };
+            let sink = audio.play_with_settings(source.to_owned(), silent_loop());
+            music_player.tracks.push(Track {
+                music,
+                sink: audio_sinks.get_handle(sink),
+                fade: 0.0,
+            });
+        }
+    }
+
+    let step = time.delta_seconds() / CROSSFADE_SECONDS;
+    let duck = if paused { PAUSE_DUCK } else { 1.0 };
+    let current = music_player.current;
+    music_player.tracks.retain_mut(|track| {
+        let Some(sink) = audio_sinks.get(&track.sink) else { return true; };
+        track.fade = if Some(track.music) == current {
+            (track.fade + step).min(1.0)
+        } else {
+            track.fade - step
+        };
+        if track.fade <= 0.0 {
+            sink.stop();
+            return false;
+        }
+        sink.set_volume(track.fade * duck * volumes.music * volumes.master);
+        true
+    });
+}
+
+// the hum of the servers while walking around
+fn play_ambience(
+    time: Res<Time>,
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    app_state: Res<State<AppState>>,
+    sound_assets: Res<SoundAssets>,
+    volumes: Res<Volumes>,
+    mut music_player: ResMut<MusicPlayer>,
+) {
+    if *app_state.current() == AppState::Loading {
+        return;
+    }
+    let ambience = match music_player.ambience.as_ref() {
+        Some(ambience) => ambience.to_owned(),
+        None => {
+            let source = sound_assets.ambience.to_owned();
+            let sink = audio_sinks.get_handle(audio.play_with_settings(source, silent_loop()));
+            music_player.ambience = Some(sink.to_owned());
+            sink
+        }
+    };
+    let Some(sink) = audio_sinks.get(&ambience) else { return; };
+
+    let step = time.delta_seconds() / CROSSFADE_SECONDS;
+    music_player.ambience_fade = if app_state.current().is_playing() {
+        (music_player.ambience_fade + step).min(1.0)
+    } else {
+        (music_player.ambience_fade - step).max(0.0)
+    };
+    sink.set_volume(music_player.ambience_fade * volumes.ambience * volumes.master);
+}
+
+// turns what happened in the game into sound effects
+fn queue_sounds(
+    app_state: Res<State<AppState>>,
+    ui_state: Res<game_ui::UiState>,
+    mut game_state: ResMut<game_backend::GameState>,
+    mut char_events: EventReader<ReceivedCharacter>,
+    mut sound_events: EventWriter<SoundEvent>,
+) {
+    if !game_state.sounds.is_empty() {
+        for sound in std::mem::take(&mut game_state.sounds) {
+            sound_events.send(SoundEvent(sound));
+        }
+    }
+    if ui_state.is_textbox_focused && app_state.current().is_playing() {
+        for _ in char_events.iter() {
+            sound_events.send(SoundEvent(Sound::Keystroke));
+        }
+    }
+}
+
+fn play_sounds(
+    audio: Res<Audio>,
+    sound_assets: Res<SoundAssets>,
+    volumes: Res<Volumes>,
+    mut sound_events: EventReader<SoundEvent>,
+) {
+    for SoundEvent(sound) in sound_events.iter() {
+        let (source, speed) = match sound {
+            Sound::Footstep => (&sound_assets.footstep, 1.0),
+            Sound::Blip(pitch) => (&sound_assets.blip, *pitch),
+            Sound::Keystroke => (&sound_assets.keystroke, 1.0),
+            Sound::Fireball => (&sound_assets.fireball, 1.0),
+        };
+        let settings = PlaybackSettings::ONCE
+            .with_volume(volumes.effects * volumes.master)
+            .with_speed(speed);
+        audio.play_with_settings(source.to_owned(), settings);
+    }
+}
+
+// loops start silent and fade in
+fn silent_loop() -> PlaybackSettings {
+    PlaybackSettings::LOOP.with_volume(0.0)
+}
+
+// browsers keep audio muted until the page is clicked or a key is pressed, so the audio
+// contexts created by the game are resumed on the first input
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen(inline_js = r#"
+export function resume_audio_on_input() {
+    const AudioContext = window.AudioContext || window.webkitAudioContext;
+    if (!AudioContext) {
+        return;
+    }
+    const contexts = [];
+    window.AudioContext = class extends AudioContext {
+        constructor(...args) {
+            super(...args);
+            contexts.push(this);
+        }
+    };
+    const events = ["pointerdown", "keydown", "touchend"];
+    const resume = () => {
+        contexts.forEach((context) => context.resume());
+        events.forEach((event) => document.removeEventListener(event, resume));
+    };
+    events.forEach((event) => document.addEventListener(event, resume));
+}
+"#)]
+extern "C" {
+    // has to be called before the app is built
+    pub fn resume_audio_on_input();
+}
+
+const CROSSFADE_SECONDS: f32 = 1.5;
+// how loud the music is behind the pause menu
+const PAUSE_DUCK: f32 = 0.4;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Music {
+    Title,
+    Explore,
+    Battle,
+}
+
+struct Track {
+    music: Music,
+    sink: Handle<AudioSink>,
+    // goes up to 1 when the track fades in and down to 0 when it fades out
+    fade: f32,
+}
+
+#[derive(Resource, Default)]
+struct MusicPlayer {
+    current: Option<Music>,
+    // the track fading in and the ones fading out
+    tracks: Vec<Track>,
+    ambience: Option<Handle<AudioSink>>,
+    ambience_fade: f32,
+}
+
+#[derive(Clone, Copy)]
+pub enum Sound {
+    Footstep,
+    // the voice of a speaker, played faster for a higher pitch
+    Blip(f32),
+    Keystroke,
+    Fireball,
+}
+
+pub struct SoundEvent(pub Sound);
+
+#[derive(Resource, Default)]
+struct SoundAssets {
+    title: Handle<AudioSource>,
+    explore: Handle<AudioSource>,
+    battle: Handle<AudioSource>,
+    ambience: Handle<AudioSource>,
+    footstep: Handle<AudioSource>,
+    blip: Handle<AudioSource>,
+    keystroke: Handle<AudioSource>,
+    fireball: Handle<AudioSource>,
+}
+
+// the volume of each channel, from 0 to 1
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Volumes {
+    pub master: f32,
+    pub music: f32,
+    pub ambience: f32,
+    pub effects: f32,
+}
+
+impl Default for Volumes {
+    fn default() -> Self {
+        Volumes {
+            master: 0.8,
+            music: 0.6,
+            ambience: 0.4,
+            effects: 0.8,
+        }
+    }
+}
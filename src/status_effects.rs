@@ -0,0 +1,190 @@
+use crate::{app_state, game_backend, npcs};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+pub struct StatusEffectsPlugin;
+
+impl Plugin for StatusEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(tick_status_effects.with_run_criteria(app_state::playing));
+    }
+}
+
+// advances the effects that are measured in seconds
+fn tick_status_effects(time: Res<Time>, mut game_state: ResMut<game_backend::GameState>) {
+    let delta = time.delta_seconds();
+    let ticked = game_state.player_effects.advance(Some(delta));
+    apply_ticks(&mut game_state, Target::Player, &ticked);
+    let ticked = game_state.enemy_effects.advance(Some(delta));
+    apply_ticks(&mut game_state, Target::Enemy, &ticked);
+}
+
+// advances the effects that are measured in turns, called after every command in battle
+pub fn end_turn(game_state: &mut game_backend::GameState) {
+    let ticked = game_state.player_effects.advance(None);
+    apply_ticks(game_state, Target::Player, &ticked);
+    let ticked = game_state.enemy_effects.advance(None);
+    apply_ticks(game_state, Target::Enemy, &ticked);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Player,
+    Enemy,
+}
+
+// burn, poison and regen do something each time they tick, the rest only last
+fn apply_ticks(
+    game_state: &mut game_backend::GameState,
+    target: Target,
+    ticked: &[(EffectKind, i32)],
+) {
+    for (kind, magnitude) in ticked.iter() {
+        match (kind, target) {
+            (EffectKind::Burn, Target::Player) | (EffectKind::Poison, Target::Player) => {
+                game_state.player_hitpoints = (game_state.player_hitpoints - magnitude).max(0);
+            }
+            (EffectKind::Burn, Target::Enemy) | (EffectKind::Poison, Target::Enemy) => {
+                game_state
+                    .action_queue
+                    .push(npcs::PlayerAction::Attack(*magnitude));
+            }
+            (EffectKind::Regen, Target::Player) => {
+                let amount = *magnitude;
+                game_state.player_hitpoints =
+                    (game_state.player_hitpoints + amount).min(game_state.effective_max_hp());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum EffectKind {
+    // loses HP every tick
+    Burn,
+    Poison,
+    // gains DEF
+    Shield,
+    // can't act
+    Stun,
+    // gains HP every tick
+    Regen,
+    // gains access levels
+    RootAccess,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum EffectDuration {
+    Turns(u32),
+    Seconds(f32),
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub struct StatusEffect {
+    pub kind: EffectKind,
+    pub duration: EffectDuration,
+    #[serde(default)]
+    pub magnitude: i32,
+    // time since the effect last ticked, for effects measured in seconds
+    #[serde(skip)]
+    timer: f32,
+}
+
+#[derive(Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffect {
+    pub fn new(kind: EffectKind, duration: EffectDuration, magnitude: i32) -> StatusEffect {
+        StatusEffect {
+            kind,
+            duration,
+            magnitude,
+            timer: 0.0,
+        }
+    }
+}
+
+impl EffectKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectKind::Burn => "Burn",
+            EffectKind::Poison => "Poison",
+            EffectKind::Shield => "Shield",
+            EffectKind::Stun => "Stun",
+            EffectKind::Regen => "Regen",
+            EffectKind::RootAccess => "Root access",
+        }
+    }
+}
+
+impl StatusEffects {
+    // applying an effect again replaces the old one
+    pub fn apply(&mut self, effect: StatusEffect) {
+        self.effects.retain(|current| current.kind != effect.kind);
+        self.effects.push(effect);
+    }
+    pub fn has(&self, kind: EffectKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+    pub fn magnitude(&self, kind: EffectKind) -> i32 {
+        self.effects
+            .iter()
+            .filter(|effect| effect.kind == kind)
+            .map(|effect| effect.magnitude)
+            .sum()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+    // advances effects by some seconds, or by one turn if `delta` is None
+    // returns the kinds and magnitudes of the effects that ticked
+    fn advance(&mut self, delta: Option<f32>) -> Vec<(EffectKind, i32)> {
+        let mut ticked = vec![];
+        for effect in self.effects.iter_mut() {
+            match (&mut effect.duration, delta) {
+                (EffectDuration::Turns(turns), None) => {
+                    ticked.push((effect.kind, effect.magnitude));
+                    *turns = turns.saturating_sub(1);
+                }
+                (EffectDuration::Seconds(seconds), Some(delta)) => {
+                    effect.timer += delta;
+                    *seconds -= delta;
+                    // effects measured in seconds tick once every second
+                    if effect.timer >= 1.0 {
+                        effect.timer -= 1.0;
+                        ticked.push((effect.kind, effect.magnitude));
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.effects.retain(|effect| match effect.duration {
+            EffectDuration::Turns(turns) => turns > 0,
+            EffectDuration::Seconds(seconds) => seconds > 0.0,
+        });
+        ticked
+    }
+    pub fn describe(&self) -> String {
+        self.effects
+            .iter()
+            .map(|effect| {
+                let duration = match effect.duration {
+                    EffectDuration::Turns(1) => "1 turn".to_string(),
+                    EffectDuration::Turns(turns) => format!("{} turns", turns),
+                    EffectDuration::Seconds(seconds) => format!("{:.0}s", seconds.ceil()),
+                };
+                match effect.kind {
+                    EffectKind::Stun => format!("{} ({})", effect.kind.name(), duration),
+                    _ => format!("{} {} ({})", effect.kind.name(), effect.magnitude, duration),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{commands, game_backend, inventory};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct StoragePlugin;
+
+impl Plugin for StoragePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveGameEvent>().add_system(save_game);
+    }
+}
+
+// sent to save the game, e.g. after an encounter ends
+pub struct SaveGameEvent;
+
+fn save_game(
+    game_state: Res<game_backend::GameState>,
+    pickups: Res<inventory::Pickups>,
+    mut save_events: EventReader<SaveGameEvent>,
+    mut result_events: EventWriter<game_backend::CommandResultEvent>,
+) {
+    if save_events.iter().count() == 0 {
+        return;
+    }
+    let data = SaveData::capture(&game_state, &pickups);
+    let output = match save(&data) {
+        Ok(()) => commands::CommandOutput::new().lines("Game saved", commands::SpanStyle::Muted),
+        Err(msg) => commands::CommandOutput::error(&format!("Error: Could not save: {}", msg)),
+    };
+    result_events.send(game_backend::CommandResultEvent(output));
+}
+
+// everything needed to continue a game
+#[derive(Serialize, Deserialize)]
+pub struct SaveData {
+    game_progress: game_backend::GameProgress,
+    player_level: i32,
+    player_hitpoints: i32,
+    player_max_hp: i32,
+    player_atk: i32,
+    player_def: i32,
+    player_x: usize,
+    player_y: usize,
+    items: Vec<(String, usize)>,
+    equipment: Vec<String>,
+    completed_npcs: Vec<String>,
+    pickups: Vec<(String, (usize, usize))>,
+    #[serde(default)]
+    explored: Vec<(usize, usize)>,
+}
+
+impl SaveData {
+    fn capture(game_state: &game_backend::GameState, pickups: &inventory::Pickups) -> SaveData {
+        let inventory = &game_state.inventory;
+        let mut explored: Vec<(usize, usize)> = game_state.explored.iter().copied().collect();
+        explored.sort();
+        SaveData {
+            game_progress: game_state.game_progress,
+            player_level: game_state.player_level,
+            player_hitpoints: game_state.player_hitpoints,
+            player_max_hp: game_state.player_max_hp,
+            player_atk: game_state.player_atk,
+            player_def: game_state.player_def,
+            player_x: game_state.player_x,
+            player_y: game_state.player_y,
+            items: inventory
+                .iter()
+                .map(|(id, count)| (id.to_string(), count))
+                .collect(),
+            equipment: inventory::EquipSlot::ALL
+                .into_iter()
+                .filter_map(|slot| inventory.equipped(slot).map(str::to_string))
+                .collect(),
+            completed_npcs: game_state.completed_npcs.clone(),
+            pickups: pickups.locations(),
+            explored,
+        }
+    }
+    // applies the save to a freshly reset game
+    pub fn restore(
+        self,
+        commands: &mut Commands,
+        game_state: &mut game_backend::GameState,
+        npcs: &mut game_backend::Npcs,
+        pickups: &mut inventory::Pickups,
+    ) {
+        game_state.game_progress = self.game_progress;
+        game_state.player_level = self.player_level;
+        game_state.player_hitpoints = self.player_hitpoints;
+        game_state.player_max_hp = self.player_max_hp;
+        game_state.player_atk = self.player_atk;
+        game_state.player_def = self.player_def;
+        game_state.player_x = self.player_x;
+        game_state.player_y = self.player_y;
+        for (id, count) in self.items.iter() {
+            game_state.inventory.add(id, *count);
+        }
+        for id in self.equipment.iter() {
+            let _ = game_state.inventory.equip(id);
+        }
+        for id in self.completed_npcs.iter() {
+            npcs.npcs.remove(id);
+        }
+        game_state.completed_npcs = self.completed_npcs;
+        game_state.explored = self.explored.into_iter().collect();
+        pickups.restore(commands, Some(self.pickups));
+    }
+}
+
+const SAVE_KEY: &str = "inferno-engineer-save";
+
+pub fn save(data: &SaveData) -> Result<(), String> {
+    let text = ron::to_string(data).map_err(|err| err.to_string())?;
+    write(SAVE_KEY, &text)
+}
+
+pub fn load() -> Option<SaveData> {
+    ron::from_str(&read(SAVE_KEY)?).ok()
+}
+
+pub fn has_save() -> bool {
+    read(SAVE_KEY).is_some()
+}
+
+// the browser's local storage on the web, files next to the game otherwise
+#[cfg(target_arch = "wasm32")]
+pub fn write(key: &str, text: &str) -> Result<(), String> {
+    let storage = web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or("local storage is not available")?;
+    storage
+        .set_item(key, text)
+        .map_err(|_| "local storage is full".to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read(key: &str) -> Option<String> {
+    let storage = web_sys::window()?.local_storage().ok()??;
+    storage.get_item(key).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn remove(key: &str) {
+    let storage = web_sys::window().and_then(|window| window.local_storage().ok().flatten());
+    if let Some(storage) = storage {
+        let _ = storage.remove_item(key);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(key: &str, text: &str) -> Result<(), String> {
+    std::fs::write(format!("{}.ron", key), text).map_err(|err| err.to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(format!("{}.ron", key)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove(key: &str) {
+    let _ = std::fs::remove_file(format!("{}.ron", key));
+}
+
+// saves a file for the player to keep, returns where it went
+#[cfg(target_arch = "wasm32")]
+pub fn export(file_name: &str, text: &str) -> Result<String, String> {
+    download(file_name, text);
+    Ok(format!("Downloading {}", file_name))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export(file_name: &str, text: &str) -> Result<String, String> {
+    std::fs::write(file_name, text).map_err(|err| err.to_string())?;
+    Ok(format!("Saved to {}", file_name))
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen(inline_js = r#"
+export function download(file_name, text) {
+    const url = URL.createObjectURL(new Blob([text], { type: "text/plain" }));
+    const link = document.createElement("a");
+    link.href = url;
+    link.download = file_name;
+    link.click();
+    URL.revokeObjectURL(url);
+}
+"#)]
+extern "C" {
+    fn download(file_name: &str, text: &str);
+}
@@ -0,0 +1,129 @@
+use crate::{game_map, loading};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+pub struct TilemapPlugin;
+
+impl Plugin for TilemapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TileChunks>()
+            .add_system(clear_chunks.before(stream_chunks))
+            .add_system(stream_chunks);
+    }
+}
+
+// spawns the chunks of tiles the camera can see and despawns the ones far away,
+// so only a few screens of tiles exist at once no matter how large the map is
+fn stream_chunks(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    map: Res<game_map::Map>,
+    tileset: Res<game_map::MapTileset>,
+    mut chunks: ResMut<TileChunks>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<Camera>>,
+) {
+    if !map.loaded || map.width == 0 || map.height == 0 || camera_query.is_empty() {
+        return;
+    }
+    let Some(window) = windows.get_primary() else { return; };
+    let (camera, projection) = camera_query.single();
+    let half_size = Vec2::new(window.width(), window.height()) * projection.scale / 2.0;
+    let center = camera.translation.truncate();
+
+    let chunk_size = Vec2::new(
+        CHUNK_SIZE as f32 * game_map::Tile::WIDTH,
+        CHUNK_SIZE as f32 * game_map::Tile::HEIGHT,
+    );
+    let chunk_count = (
+        (map.width + CHUNK_SIZE - 1) / CHUNK_SIZE,
+        (map.height + CHUNK_SIZE - 1) / CHUNK_SIZE,
+    );
+    let min = ((center - half_size) / chunk_size).floor();
+    let max = ((center + half_size) / chunk_size).floor();
+    let in_range = |(x, y): (usize, usize), margin: f32| {
+        let (x, y) = (x as f32, y as f32);
+        x >= min.x - margin && x <= max.x + margin && y >= min.y - margin && y <= max.y + margin
+    };
+
+    // keep one chunk around the screen so walking back and forth doesn't respawn tiles
+    chunks.spawned.retain(|&chunk, entity| {
+        let keep = in_range(chunk, 1.0);
+        if !keep {
+            commands.entity(*entity).despawn_recursive();
+        }
+        keep
+    });
+
+    for chunk_x in 0..chunk_count.0 {
+        for chunk_y in 0..chunk_count.1 {
+            let chunk = (chunk_x, chunk_y);
+            if !in_range(chunk, 0.0) || chunks.spawned.contains_key(&chunk) {
+                continue;
+            }
+            let entity = spawn_chunk(&mut commands, &map, &tileset, chunk);
+            chunks.spawned.insert(chunk, entity);
+        }
+    }
+}
+
+// the tiles are spawned again from the new map after the map file is edited
+fn clear_chunks(
+    mut commands: Commands,
+    mut chunks: ResMut<TileChunks>,
+    mut reload_events: EventReader<loading::ReloadEvent>,
+) {
+    if !reload_events
+        .iter()
+        .any(|event| matches!(event, loading::ReloadEvent::Map))
+    {
+        return;
+    }
+    for (_, entity) in chunks.spawned.drain() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_chunk(
+    commands: &mut Commands,
+    map: &game_map::Map,
+    tileset: &game_map::MapTileset,
+    (chunk_x, chunk_y): (usize, usize),
+) -> Entity {
+    let origin = (chunk_x * CHUNK_SIZE, chunk_y * CHUNK_SIZE);
+    commands
+        .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+            origin.0 as f32 * game_map::Tile::WIDTH,
+            origin.1 as f32 * game_map::Tile::HEIGHT,
+            game_map::Tile::Z_LAYER,
+        )))
+        .with_children(|parent| {
+            for x in origin.0..(origin.0 + CHUNK_SIZE).min(map.width) {
+                for y in origin.1..(origin.1 + CHUNK_SIZE).min(map.height) {
+                    parent.spawn(SpriteSheetBundle {
+                        transform: Transform::from_xyz(
+                            (x - origin.0) as f32 * game_map::Tile::WIDTH,
+                            (y - origin.1) as f32 * game_map::Tile::HEIGHT,
+                            0.0,
+                        ),
+                        sprite: TextureAtlasSprite {
+                            index: map.tiles[x][y].tile_type,
+                            anchor: bevy::sprite::Anchor::Custom(Vec2::new(-0.5, -0.5)),
+                            ..default()
+                        },
+                        texture_atlas: tileset.0.to_owned(),
+                        ..default()
+                    });
+                }
+            }
+        })
+        .id()
+}
+
+// the number of tiles on each side of a chunk
+const CHUNK_SIZE: usize = 8;
+
+// the chunks of tiles that are currently spawned, by chunk coordinates
+#[derive(Resource, Default)]
+pub struct TileChunks {
+    spawned: HashMap<(usize, usize), Entity>,
+}
@@ -0,0 +1,224 @@
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+// how a speaker's lines are typed out, set for each npc in the npc file
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Voice {
+    // characters per second
+    pub speed: f32,
+    // the playback speed of the blip sound, higher sounds higher
+    pub pitch: f32,
+    // a blip is played every this many characters
+    pub blip_every: usize,
+}
+
+impl Default for Voice {
+    fn default() -> Self {
+        Voice {
+            speed: 40.0,
+            pitch: 1.0,
+            blip_every: 2,
+        }
+    }
+}
+
+// text that is revealed one character at a time, with inline markup:
+//   *emphasis*
+//   {pause=0.5} waits for half a second
+//   {speed=0.5}slowly{/speed}
+//   {color=red}red{/color}, also blue, green, yellow, gray or #rrggbb
+//   {shake}shaking{/shake}
+pub struct Typewriter {
+    glyphs: Vec<Glyph>,
+    voice: Voice,
+    elapsed: f32,
+    // the number of glyphs shown so far
+    revealed: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Style {
+    emphasis: bool,
+    color: Option<egui::Color32>,
+    shake: bool,
+}
+
+struct Glyph {
+    c: char,
+    style: Style,
+    // seconds after the start when the glyph shows up
+    at: f32,
+}
+
+impl Typewriter {
+    pub fn new(markup: &str, voice: &Voice) -> Typewriter {
+        let mut glyphs = vec![];
+        let mut style = Style::default();
+        let mut speed = 1.0;
+        let mut time = 0.0;
+        let mut rest = markup;
+        while let Some(c) = rest.chars().next() {
+            if c == '*' {
+                style.emphasis = !style.emphasis;
+                rest = &rest[1..];
+                continue;
+            }
+            if c == '{' {
+                let tag = rest.find('}').map(|end| (&rest[1..end], end));
+                if let Some((tag, end)) = tag {
+                    if apply_tag(tag, &mut style, &mut speed, &mut time) {
+                        rest = &rest[end + 1..];
+                        continue;
+                    }
+                }
+            }
+
+            time += 1.0 / (voice.speed * speed).max(1.0);
+            glyphs.push(Glyph { c, style, at: time });
+            // let sentences sink in
+            time += match c {
+                '.' | '!' | '?' => SENTENCE_PAUSE,
+                ',' | ';' | ':' => COMMA_PAUSE,
+                _ => 0.0,
+            };
+            rest = &rest[c.len_utf8()..];
+        }
+        Typewriter {
+            glyphs,
+            voice: voice.clone(),
+            elapsed: 0.0,
+            revealed: 0,
+        }
+    }
+    // text that is shown in full right away
+    pub fn revealed(markup: &str) -> Typewriter {
+        let mut typewriter = Typewriter::new(markup, &Voice::default());
+        typewriter.skip();
+        typewriter
+    }
+    // reveals the characters due, returns the pitch of the blip to play if any
+    pub fn update(&mut self, delta: f32) -> Option<f32> {
+        if self.is_done() {
+            return None;
+        }
+        self.elapsed += delta;
+        let visible = self.glyphs.partition_point(|glyph| glyph.at <= self.elapsed);
+        let blip = (self.revealed..visible).any(|index| {
+            !self.glyphs[index].c.is_whitespace() && index % self.voice.blip_every.max(1) == 0
+        });
+        self.revealed = visible;
+        blip.then_some(self.voice.pitch)
+    }
+    pub fn skip(&mut self) {
+        self.revealed = self.glyphs.len();
+        self.elapsed = self.glyphs.last().map_or(0.0, |glyph| glyph.at);
+    }
+    pub fn is_done(&self) -> bool {
+        self.revealed >= self.glyphs.len()
+    }
+    // the whole text without the markup
+    pub fn plain_text(&self) -> String {
+        self.glyphs.iter().map(|glyph| glyph.c).collect()
+    }
+    // shows the revealed part of the text, clicking it reveals the rest.
+    // shaking text stands still unless `animate` is set
+    pub fn show(&self, ui: &mut egui::Ui, animate: bool) -> egui::Response {
+        let visible = &self.glyphs[..self.revealed];
+        let font_id = egui::TextStyle::Body.resolve(ui.style());
+        let text_color = ui.visuals().text_color();
+        let strong_color = ui.visuals().strong_text_color();
+        let color_of = |style: &Style| match (style.color, style.emphasis) {
+            (Some(color), _) => color,
+            (None, true) => strong_color,
+            (None, false) => text_color,
+        };
+
+        let mut job = egui::text::LayoutJob::default();
+        job.wrap.max_width = ui.available_width();
+        for glyph in visible {
+            let format = egui::TextFormat {
+                font_id: font_id.clone(),
+                // shaking glyphs are painted separately
+                color: if glyph.style.shake && animate {
+                    egui::Color32::TRANSPARENT
+                } else {
+                    color_of(&glyph.style)
+                },
+                italics: glyph.style.emphasis,
+                ..Default::default()
+            };
+            job.append(&glyph.c.to_string(), 0.0, format);
+        }
+        let galley = ui.fonts().layout_job(job);
+        let (rect, response) = ui.allocate_exact_size(galley.size(), egui::Sense::click());
+
+        let time = ui.input().time as f32;
+        for (index, glyph) in visible.iter().enumerate() {
+            if !glyph.style.shake || !animate {
+                continue;
+            }
+            let cursor = galley.from_ccursor(egui::text::CCursor::new(index));
+            let pos = rect.min + galley.pos_from_cursor(&cursor).min.to_vec2();
+            let phase = time * SHAKE_SPEED + index as f32 * 1.7;
+            let offset = egui::vec2(phase.sin(), (phase * 1.3).cos()) * SHAKE_AMPLITUDE;
+            ui.painter().text(
+                pos + offset,
+                egui::Align2::LEFT_TOP,
+                glyph.c,
+                font_id.clone(),
+                color_of(&glyph.style),
+            );
+        }
+        ui.painter().galley(rect.min, galley);
+        response
+    }
+}
+
+// applies a markup tag, returns false if it isn't one
+fn apply_tag(tag: &str, style: &mut Style, speed: &mut f32, time: &mut f32) -> bool {
+    let (name, value) = tag.split_once('=').unwrap_or((tag, ""));
+    match name {
+        // the glyphs are kept in the order they show up, time can't go backwards
+        "pause" => match value.parse::<f32>() {
+            Ok(seconds) if seconds.is_finite() => *time += seconds.max(0.0),
+            _ => return false,
+        },
+        "speed" => match value.parse::<f32>() {
+            Ok(multiplier) if multiplier > 0.0 => *speed = multiplier,
+            _ => return false,
+        },
+        "/speed" => *speed = 1.0,
+        "color" => match parse_color(value) {
+            Some(color) => style.color = Some(color),
+            None => return false,
+        },
+        "/color" => style.color = None,
+        "shake" => style.shake = true,
+        "/shake" => style.shake = false,
+        _ => return false,
+    }
+    true
+}
+
+fn parse_color(value: &str) -> Option<egui::Color32> {
+    let color = match value {
+        "red" => egui::Color32::from_rgb(226, 45, 42),
+        "blue" => egui::Color32::from_rgb(80, 140, 242),
+        "green" => egui::Color32::from_rgb(90, 200, 120),
+        "yellow" => egui::Color32::from_rgb(240, 200, 60),
+        "gray" => egui::Color32::from_rgb(150, 150, 150),
+        _ => {
+            let hex = value.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+            let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+            egui::Color32::from_rgb(channel(0)?, channel(2)?, channel(4)?)
+        }
+    };
+    Some(color)
+}
+
+const SENTENCE_PAUSE: f32 = 0.25;
+const COMMA_PAUSE: f32 = 0.1;
+const SHAKE_SPEED: f32 = 30.0;
+// how far shaking glyphs move in points
+const SHAKE_AMPLITUDE: f32 = 1.5;